@@ -0,0 +1,59 @@
+#!/usr/bin/env python3
+# Generates src/lexer/unicode/tables.rs from the Unicode database that ships with Python.
+#
+#     python3 scripts/unicode.py > src/lexer/unicode/tables.rs
+
+import sys
+import unicodedata
+
+OTHER_ID_START = [0x1885, 0x1886, 0x2118, 0x212E, 0x309B, 0x309C]
+OTHER_ID_CONTINUE = [0x00B7, 0x0387] + list(range(0x1369, 0x1372)) + [0x19DA]
+PATTERN_SYNTAX = [
+    (0x21, 0x2F), (0x3A, 0x40), (0x5B, 0x5E), (0x60, 0x60), (0x7B, 0x7E), (0xA1, 0xA7), (0xA9, 0xA9),
+    (0xAB, 0xAC), (0xAE, 0xAE), (0xB0, 0xB1), (0xB6, 0xB6), (0xBB, 0xBB), (0xBF, 0xBF), (0xD7, 0xD7),
+    (0xF7, 0xF7), (0x2010, 0x2027), (0x2030, 0x203E), (0x2041, 0x2053), (0x2055, 0x205E),
+    (0x2190, 0x245F), (0x2500, 0x2775), (0x2794, 0x2BFF), (0x2E00, 0x2E7F), (0x3001, 0x3003),
+    (0x3008, 0x3020), (0x3030, 0x3030), (0xFD3E, 0xFD3F), (0xFE45, 0xFE46),
+]
+PATTERN_WHITE_SPACE = [(0x09, 0x0D), (0x20, 0x20), (0x85, 0x85), (0x200E, 0x200F), (0x2028, 0x2029)]
+
+
+def excluded(cp):
+    return any(lo <= cp <= hi for lo, hi in PATTERN_SYNTAX + PATTERN_WHITE_SPACE)
+
+
+def id_start(cp):
+    category = unicodedata.category(chr(cp))
+    return (category in ('Lu', 'Ll', 'Lt', 'Lm', 'Lo', 'Nl') or cp in OTHER_ID_START) and not excluded(cp)
+
+
+def id_continue(cp):
+    category = unicodedata.category(chr(cp))
+    return (id_start(cp) or category in ('Mn', 'Mc', 'Nd', 'Pc') or cp in OTHER_ID_CONTINUE) and not excluded(cp)
+
+
+def ranges(predicate):
+    out = []
+    for cp in range(0x110000):
+        if 0xD800 <= cp <= 0xDFFF or not predicate(cp):
+            continue
+        if out and out[-1][1] == cp - 1:
+            out[-1][1] = cp
+        else:
+            out.append([cp, cp])
+    return out
+
+
+def table(name, items):
+    lines = ['pub const %s: &[(char, char)] = &[' % name]
+    for lo, hi in items:
+        lines.append("    ('\\u{%x}', '\\u{%x}')," % (lo, hi))
+    lines.append('];')
+    return '\n'.join(lines)
+
+
+print('// Generated by scripts/unicode.py from Unicode %s, do not edit.' % unicodedata.unidata_version)
+print()
+print(table('ID_START', ranges(id_start)))
+print()
+print(table('ID_CONTINUE', ranges(id_continue)))
@@ -0,0 +1,688 @@
+//! Serializes a parsed `Program` into ESTree JSON, the format used by acorn, esprima and eslint.
+
+use ast::node::*;
+use lexer::enums::{LiteralType, SourceType};
+use lexer::token::{Position, Span};
+use lexer::unicode::is_line_terminator;
+
+/// Returns the ESTree JSON for `program`, `source` is the text it was parsed from.
+pub fn to_json(program: &Program, source: &str) -> String {
+    Serializer::new(source).program(program)
+}
+
+fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 || c == '\u{2028}' || c == '\u{2029}' => {
+                out.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => out.push(c)
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn array(items: Vec<String>) -> String {
+    format!("[{}]", items.join(","))
+}
+
+fn option(item: Option<String>) -> String {
+    item.unwrap_or(String::from("null"))
+}
+
+fn boolean(b: bool) -> String {
+    String::from(if b { "true" } else { "false" })
+}
+
+fn number(f: f64) -> String {
+    if f.is_finite() {
+        format!("{}", f)
+    } else {
+        String::from("null")
+    }
+}
+
+fn unary_operator(operator: UnaryOperator) -> &'static str {
+    match operator {
+        UnaryOperator::Minus => "-",
+        UnaryOperator::Plus => "+",
+        UnaryOperator::Invert => "!",
+        UnaryOperator::Tilde => "~",
+        UnaryOperator::Typeof => "typeof",
+        UnaryOperator::Void => "void",
+        UnaryOperator::Delete => "delete"
+    }
+}
+
+fn update_operator(operator: UpdateOperator) -> &'static str {
+    match operator {
+        UpdateOperator::Increment => "++",
+        UpdateOperator::Decrement => "--"
+    }
+}
+
+fn binary_operator(operator: BinaryOperator) -> &'static str {
+    match operator {
+        BinaryOperator::IsEqual => "==",
+        BinaryOperator::IsNotEqual => "!=",
+        BinaryOperator::IsSame => "===",
+        BinaryOperator::IsNotSame => "!==",
+        BinaryOperator::SmallThan => "<",
+        BinaryOperator::SmallAndEqualThan => "<=",
+        BinaryOperator::GreaterThan => ">",
+        BinaryOperator::GreaterAndEqualThan => ">=",
+        BinaryOperator::LeftShift => "<<",
+        BinaryOperator::RightShift => ">>",
+        BinaryOperator::RightShiftUnsigned => ">>>",
+        BinaryOperator::Plus => "+",
+        BinaryOperator::Minus => "-",
+        BinaryOperator::Multiple => "*",
+        BinaryOperator::Divide => "/",
+        BinaryOperator::Mod => "%",
+        BinaryOperator::Exp => "**",
+        BinaryOperator::OrBitwise => "|",
+        BinaryOperator::Xor => "^",
+        BinaryOperator::AndBitwise => "&",
+        BinaryOperator::In => "in",
+        BinaryOperator::Instanceof => "instanceof"
+    }
+}
+
+fn logical_operator(operator: LogicalOperator) -> &'static str {
+    match operator {
+        LogicalOperator::Or => "||",
+        LogicalOperator::And => "&&"
+    }
+}
+
+fn assignment_operator(operator: AssignmentOperator) -> &'static str {
+    match operator {
+        AssignmentOperator::Equal => "=",
+        AssignmentOperator::PlusAssign => "+=",
+        AssignmentOperator::MinusAssign => "-=",
+        AssignmentOperator::MultipleAssign => "*=",
+        AssignmentOperator::DivideAssign => "/=",
+        AssignmentOperator::ModAssign => "%=",
+        AssignmentOperator::ExpAssign => "**=",
+        AssignmentOperator::LeftShiftAssign => "<<=",
+        AssignmentOperator::RightShiftAssign => ">>=",
+        AssignmentOperator::RightShiftUnsignedAssign => ">>>=",
+        AssignmentOperator::OrBitwiseAssign => "|=",
+        AssignmentOperator::XorAssign => "^=",
+        AssignmentOperator::AndBitwiseAssign => "&="
+    }
+}
+
+fn property_kind(kind: PropertyKind) -> &'static str {
+    match kind {
+        PropertyKind::Init => "init",
+        PropertyKind::Get => "get",
+        PropertyKind::Set => "set"
+    }
+}
+
+fn method_kind(kind: MethodKind) -> &'static str {
+    match kind {
+        MethodKind::Constructor => "constructor",
+        MethodKind::Method => "method",
+        MethodKind::Get => "get",
+        MethodKind::Set => "set"
+    }
+}
+
+fn variable_kind(kind: VariableKind) -> &'static str {
+    match kind {
+        VariableKind::Var => "var",
+        VariableKind::Let => "let",
+        VariableKind::Const => "const"
+    }
+}
+
+struct Serializer<'a> {
+    source: &'a str,
+    end: Position,
+    /// Byte offset where each line starts, to count columns from.
+    lines: Vec<usize>,
+    /// Byte and UTF-16 offset after each non-ASCII character. Spans hold byte offsets while
+    /// ESTree counts UTF-16 code units, like JavaScript strings do.
+    utf16: Vec<(usize, usize)>
+}
+
+impl<'a> Serializer<'a> {
+    fn new(source: &'a str) -> Serializer<'a> {
+        let mut lines = vec![0];
+        let mut utf16 = Vec::new();
+        let mut units = 0;
+        let mut chars = source.char_indices().peekable();
+        while let Some((offset, c)) = chars.next() {
+            let end = offset + c.len_utf8();
+            units += c.len_utf16();
+            if !c.is_ascii() {
+                utf16.push((end, units));
+            }
+            // `\r\n` ends a single line
+            if is_line_terminator(c) && !(c == '\r' && chars.peek().map(|&(_, c)| c) == Some('\n')) {
+                lines.push(end);
+            }
+        }
+        let col = source[lines[lines.len() - 1]..].chars().count() + 1;
+        Serializer {
+            source: source,
+            end: Position::new(source.len(), lines.len() as u64, col as u32),
+            lines: lines,
+            utf16: utf16
+        }
+    }
+
+    fn raw(&self, span: &Span) -> &'a str {
+        match self.source.get(span.start.offset..span.end.offset) {
+            Some(raw) => raw,
+            None => ""
+        }
+    }
+
+    /// The UTF-16 offset of the byte `offset`.
+    fn utf16_offset(&self, offset: usize) -> usize {
+        match self.utf16.binary_search_by_key(&offset, |&(end, _)| end) {
+            Ok(index) => self.utf16[index].1,
+            Err(0) => offset,
+            Err(index) => {
+                let (end, units) = self.utf16[index - 1];
+                units + offset - end
+            }
+        }
+    }
+
+    fn position(&self, position: &Position) -> String {
+        let column = match self.lines.get(position.line.saturating_sub(1) as usize) {
+            Some(&start) if start <= position.offset => self.utf16_offset(position.offset) - self.utf16_offset(start),
+            _ => position.col.saturating_sub(1) as usize
+        };
+        format!("{{\"line\":{},\"column\":{}}}", position.line, column)
+    }
+
+    fn node(&self, kind: &str, span: &Span, fields: Vec<(&str, String)>) -> String {
+        let mut out = format!("{{\"type\":{}", string(kind));
+        for (name, value) in fields {
+            out.push_str(&format!(",{}:{}", string(name), value));
+        }
+        out.push_str(&format!(",\"loc\":{{\"start\":{},\"end\":{}}},\"range\":[{},{}]}}",
+                              self.position(&span.start), self.position(&span.end),
+                              self.utf16_offset(span.start.offset), self.utf16_offset(span.end.offset)));
+        out
+    }
+
+    fn program(&self, program: &Program) -> String {
+        let span = Span::new(Position::new(0, 1, 1), self.end);
+        self.node("Program", &span, vec![
+            ("body", self.body(&program.body)),
+            ("sourceType", string(match program.source_type {
+                SourceType::Script => "script",
+                SourceType::Module => "module"
+            }))
+        ])
+    }
+
+    fn identifier(&self, name: &Node<String>) -> String {
+        self.node("Identifier", &name.span, vec![("name", string(&name.item))])
+    }
+
+    fn literal(&self, span: &Span, literal: &LiteralType) -> String {
+        let mut fields = vec![];
+        match *literal {
+            LiteralType::String(ref s, _) => fields.push(("value", string(s))),
+            LiteralType::Regex(ref pattern, ref flags) => {
+                fields.push(("value", String::from("null")));
+                fields.push(("regex", format!("{{\"pattern\":{},\"flags\":{}}}",
+                                              string(pattern), string(flags))));
+            }
+            LiteralType::Integer(i) => fields.push(("value", i.to_string())),
+            LiteralType::Float(f) => fields.push(("value", number(f))),
+            LiteralType::BigInt(_) => {
+                // the source text like acorn has it, `0x1_0n` is `"0x10"`
+                let raw = self.raw(span);
+                let digits: String = raw[..raw.len().saturating_sub(1)].chars().filter(|&c| c != '_').collect();
+                fields.push(("value", String::from("null")));
+                fields.push(("bigint", string(&digits)));
+            }
+            LiteralType::Boolean(b) => fields.push(("value", boolean(b))),
+            LiteralType::Null => fields.push(("value", String::from("null")))
+        }
+        fields.push(("raw", string(self.raw(span))));
+        self.node("Literal", span, fields)
+    }
+
+    /// Statement list of a program or function body, the leading string literal statements
+    /// form its directive prologue.
+    fn body(&self, body: &[Node<Statement>]) -> String {
+        let mut prologue = true;
+        let mut items = Vec::new();
+        for stmt in body {
+            let directive = match stmt.item {
+                Statement::Expression(Node { item: Expression::Literal(LiteralType::String(_, ref raw)), .. }) if prologue => {
+                    Some(String::from(raw.get(1..raw.len().saturating_sub(1)).unwrap_or("")))
+                }
+                _ => None
+            };
+            match directive {
+                Some(directive) => {
+                    if let Statement::Expression(ref expr) = stmt.item {
+                        items.push(self.node("ExpressionStatement", &stmt.span, vec![
+                            ("expression", self.expression(expr)),
+                            ("directive", string(&directive))
+                        ]));
+                    }
+                }
+                None => {
+                    prologue = false;
+                    items.push(self.statement(stmt));
+                }
+            }
+        }
+        array(items)
+    }
+
+    fn statements(&self, body: &[Node<Statement>]) -> String {
+        array(body.iter().map(|stmt| self.statement(stmt)).collect())
+    }
+
+    fn block(&self, block: &Node<Vec<Node<Statement>>>) -> String {
+        self.node("BlockStatement", &block.span, vec![("body", self.statements(&block.item))])
+    }
+
+    fn function_body(&self, block: &Node<Vec<Node<Statement>>>) -> String {
+        self.node("BlockStatement", &block.span, vec![("body", self.body(&block.item))])
+    }
+
+    fn function(&self, kind: &str, span: &Span, function: &Function) -> String {
+        self.node(kind, span, vec![
+            ("id", option(function.id.as_ref().map(|id| self.identifier(id)))),
+            ("params", self.patterns(&function.params)),
+            ("body", self.function_body(&function.body)),
+            ("generator", boolean(function.generator)),
+            ("expression", boolean(false)),
+            ("async", boolean(function.is_async))
+        ])
+    }
+
+    fn class(&self, kind: &str, span: &Span, class: &Class) -> String {
+        let body = class.body.item.iter().map(|element| match element.item {
+            ClassElement::Method { ref key, ref value, kind, is_static } => {
+                let (key, computed) = self.property_key(key);
+                self.node("MethodDefinition", &element.span, vec![
+                    ("key", key),
+                    ("value", self.expression(value)),
+                    ("kind", string(method_kind(kind))),
+                    ("computed", boolean(computed)),
+                    ("static", boolean(is_static))
+                ])
+            }
+            ClassElement::Field { ref key, ref value, is_static } => {
+                let (key, computed) = self.property_key(key);
+                self.node("PropertyDefinition", &element.span, vec![
+                    ("key", key),
+                    ("value", option(value.as_ref().map(|value| self.expression(value)))),
+                    ("computed", boolean(computed)),
+                    ("static", boolean(is_static))
+                ])
+            }
+            ClassElement::StaticBlock(ref body) => {
+                self.node("StaticBlock", &element.span, vec![("body", self.statements(body))])
+            }
+        }).collect();
+        self.node(kind, span, vec![
+            ("id", option(class.id.as_ref().map(|id| self.identifier(id)))),
+            ("superClass", option(class.super_class.as_ref().map(|super_class| self.expression(super_class)))),
+            ("body", self.node("ClassBody", &class.body.span, vec![("body", array(body))]))
+        ])
+    }
+
+    fn statement(&self, stmt: &Node<Statement>) -> String {
+        let span = &stmt.span;
+        match stmt.item {
+            Statement::Empty => self.node("EmptyStatement", span, vec![]),
+            Statement::Debugger => self.node("DebuggerStatement", span, vec![]),
+            Statement::Expression(ref expr) => {
+                self.node("ExpressionStatement", span, vec![("expression", self.expression(expr))])
+            }
+            Statement::Block(ref body) => self.node("BlockStatement", span, vec![("body", self.statements(body))]),
+            Statement::If { ref test, ref consequent, ref alternate } => {
+                self.node("IfStatement", span, vec![
+                    ("test", self.expression(test)),
+                    ("consequent", self.statement(consequent)),
+                    ("alternate", option(alternate.as_ref().map(|alternate| self.statement(alternate))))
+                ])
+            }
+            Statement::Labeled { ref label, ref body } => {
+                self.node("LabeledStatement", span, vec![
+                    ("label", self.identifier(label)),
+                    ("body", self.statement(body))
+                ])
+            }
+            Statement::Break(ref label) => {
+                self.node("BreakStatement", span, vec![("label", option(label.as_ref().map(|label| self.identifier(label))))])
+            }
+            Statement::Continue(ref label) => {
+                self.node("ContinueStatement", span, vec![("label", option(label.as_ref().map(|label| self.identifier(label))))])
+            }
+            Statement::With { ref object, ref body } => {
+                self.node("WithStatement", span, vec![
+                    ("object", self.expression(object)),
+                    ("body", self.statement(body))
+                ])
+            }
+            Statement::Switch { ref discriminant, ref cases } => {
+                let cases = cases.iter().map(|case| self.node("SwitchCase", &case.span, vec![
+                    ("test", option(case.item.test.as_ref().map(|test| self.expression(test)))),
+                    ("consequent", self.statements(&case.item.consequent))
+                ])).collect();
+                self.node("SwitchStatement", span, vec![
+                    ("discriminant", self.expression(discriminant)),
+                    ("cases", array(cases))
+                ])
+            }
+            Statement::Return(ref argument) => {
+                self.node("ReturnStatement", span, vec![("argument", option(argument.as_ref().map(|argument| self.expression(argument))))])
+            }
+            Statement::Throw(ref argument) => {
+                self.node("ThrowStatement", span, vec![("argument", self.expression(argument))])
+            }
+            Statement::Try { ref block, ref handler, ref finalizer } => {
+                let handler = handler.as_ref().map(|handler| self.node("CatchClause", &handler.span, vec![
+                    ("param", self.pattern(&handler.item.param)),
+                    ("body", self.block(&handler.item.body))
+                ]));
+                self.node("TryStatement", span, vec![
+                    ("block", self.block(block)),
+                    ("handler", option(handler)),
+                    ("finalizer", option(finalizer.as_ref().map(|finalizer| self.block(finalizer))))
+                ])
+            }
+            Statement::While { ref test, ref body } => {
+                self.node("WhileStatement", span, vec![
+                    ("test", self.expression(test)),
+                    ("body", self.statement(body))
+                ])
+            }
+            Statement::Declaration(Declaration::Function(ref function)) => {
+                self.function("FunctionDeclaration", span, function)
+            }
+            Statement::DoWhile { ref body, ref test } => {
+                self.node("DoWhileStatement", span, vec![
+                    ("body", self.statement(body)),
+                    ("test", self.expression(test))
+                ])
+            }
+            Statement::For { ref init, ref test, ref update, ref body } => {
+                let init = init.as_ref().map(|init| match *init {
+                    ForInit::Variable(ref declaration) => self.variable_declaration(&declaration.span, &declaration.item),
+                    ForInit::Expression(ref expr) => self.expression(expr)
+                });
+                self.node("ForStatement", span, vec![
+                    ("init", option(init)),
+                    ("test", option(test.as_ref().map(|test| self.expression(test)))),
+                    ("update", option(update.as_ref().map(|update| self.expression(update)))),
+                    ("body", self.statement(body))
+                ])
+            }
+            Statement::ForIn { ref left, ref right, ref body } => {
+                self.node("ForInStatement", span, vec![
+                    ("left", self.for_target(left)),
+                    ("right", self.expression(right)),
+                    ("body", self.statement(body))
+                ])
+            }
+            Statement::ForOf { ref left, ref right, ref body, is_await } => {
+                self.node("ForOfStatement", span, vec![
+                    ("left", self.for_target(left)),
+                    ("right", self.expression(right)),
+                    ("body", self.statement(body)),
+                    ("await", boolean(is_await))
+                ])
+            }
+            Statement::Declaration(Declaration::Variable(ref declaration)) => {
+                self.variable_declaration(span, declaration)
+            }
+            Statement::Declaration(Declaration::Class(ref class)) => self.class("ClassDeclaration", span, class),
+        }
+    }
+
+    fn variable_declaration(&self, span: &Span, declaration: &VariableDeclaration) -> String {
+        let declarations = declaration.declarations.iter().map(|declarator| self.node("VariableDeclarator", &declarator.span, vec![
+            ("id", self.pattern(&declarator.item.id)),
+            ("init", option(declarator.item.init.as_ref().map(|init| self.expression(init))))
+        ])).collect();
+        self.node("VariableDeclaration", span, vec![
+            ("declarations", array(declarations)),
+            ("kind", string(variable_kind(declaration.kind)))
+        ])
+    }
+
+    fn for_target(&self, target: &ForTarget) -> String {
+        match *target {
+            ForTarget::Variable(ref declaration) => self.variable_declaration(&declaration.span, &declaration.item),
+            ForTarget::Pattern(ref pattern) => self.pattern(pattern)
+        }
+    }
+
+    fn expressions(&self, expressions: &[Node<Expression>]) -> String {
+        array(expressions.iter().map(|expr| self.expression(expr)).collect())
+    }
+
+    fn property_key(&self, key: &Node<PropertyKey>) -> (String, bool) {
+        match key.item {
+            PropertyKey::Identifier(ref name) => {
+                (self.node("Identifier", &key.span, vec![("name", string(name))]), false)
+            }
+            PropertyKey::Literal(ref literal) => (self.literal(&key.span, literal), false),
+            PropertyKey::Computed(ref expr) => (self.expression(expr), true),
+            PropertyKey::Private(ref name) => {
+                (self.node("PrivateIdentifier", &key.span, vec![("name", string(name))]), false)
+            }
+        }
+    }
+
+    fn property(&self, property: &Node<Property>) -> String {
+        let (key, computed) = self.property_key(&property.item.key);
+        self.node("Property", &property.span, vec![
+            ("key", key),
+            ("value", self.expression(&property.item.value)),
+            ("kind", string(property_kind(property.item.kind))),
+            ("method", boolean(property.item.method)),
+            ("shorthand", boolean(property.item.shorthand)),
+            ("computed", boolean(computed))
+        ])
+    }
+
+    fn expression(&self, expr: &Node<Expression>) -> String {
+        let span = &expr.span;
+        match expr.item {
+            Expression::This => self.node("ThisExpression", span, vec![]),
+            Expression::Super => self.node("Super", span, vec![]),
+            Expression::Identifier(ref name) => self.node("Identifier", span, vec![("name", string(name))]),
+            Expression::PrivateName(ref name) => self.node("PrivateIdentifier", span, vec![("name", string(name))]),
+            Expression::Literal(ref literal) => self.literal(span, literal),
+            Expression::Array(ref elements) => {
+                let elements = elements.iter().map(|element| option(element.as_ref().map(|element| self.expression(element)))).collect();
+                self.node("ArrayExpression", span, vec![("elements", array(elements))])
+            }
+            Expression::Object(ref properties) => {
+                let properties = properties.iter().map(|property| self.property(property)).collect();
+                self.node("ObjectExpression", span, vec![("properties", array(properties))])
+            }
+            Expression::Function(ref function) => self.function("FunctionExpression", span, function),
+            Expression::Class(ref class) => self.class("ClassExpression", span, class),
+            Expression::Arrow { ref params, ref body, is_async } => {
+                let (body, expression) = match *body {
+                    ArrowBody::Block(ref block) => (self.function_body(block), false),
+                    ArrowBody::Expression(ref expr) => (self.expression(expr), true)
+                };
+                self.node("ArrowFunctionExpression", span, vec![
+                    ("id", String::from("null")),
+                    ("params", self.patterns(params)),
+                    ("body", body),
+                    ("generator", boolean(false)),
+                    ("expression", boolean(expression)),
+                    ("async", boolean(is_async))
+                ])
+            }
+            Expression::Unary { operator, ref argument } => {
+                self.node("UnaryExpression", span, vec![
+                    ("operator", string(unary_operator(operator))),
+                    ("prefix", boolean(true)),
+                    ("argument", self.expression(argument))
+                ])
+            }
+            Expression::Update { operator, prefix, ref argument } => {
+                self.node("UpdateExpression", span, vec![
+                    ("operator", string(update_operator(operator))),
+                    ("prefix", boolean(prefix)),
+                    ("argument", self.expression(argument))
+                ])
+            }
+            Expression::Binary { operator, ref left, ref right } => {
+                self.node("BinaryExpression", span, vec![
+                    ("operator", string(binary_operator(operator))),
+                    ("left", self.expression(left)),
+                    ("right", self.expression(right))
+                ])
+            }
+            Expression::Logical { operator, ref left, ref right } => {
+                self.node("LogicalExpression", span, vec![
+                    ("operator", string(logical_operator(operator))),
+                    ("left", self.expression(left)),
+                    ("right", self.expression(right))
+                ])
+            }
+            Expression::Assignment { operator, ref left, ref right } => {
+                self.node("AssignmentExpression", span, vec![
+                    ("operator", string(assignment_operator(operator))),
+                    ("left", self.pattern(left)),
+                    ("right", self.expression(right))
+                ])
+            }
+            Expression::Conditional { ref test, ref consequent, ref alternate } => {
+                self.node("ConditionalExpression", span, vec![
+                    ("test", self.expression(test)),
+                    ("consequent", self.expression(consequent)),
+                    ("alternate", self.expression(alternate))
+                ])
+            }
+            Expression::Call { ref callee, ref arguments } => {
+                self.node("CallExpression", span, vec![
+                    ("callee", self.expression(callee)),
+                    ("arguments", self.expressions(arguments))
+                ])
+            }
+            Expression::New { ref callee, ref arguments } => {
+                self.node("NewExpression", span, vec![
+                    ("callee", self.expression(callee)),
+                    ("arguments", self.expressions(arguments))
+                ])
+            }
+            Expression::Member { ref object, ref property, computed } => {
+                self.node("MemberExpression", span, vec![
+                    ("object", self.expression(object)),
+                    ("property", self.expression(property)),
+                    ("computed", boolean(computed))
+                ])
+            }
+            Expression::MetaProperty { ref meta, ref property } => {
+                self.node("MetaProperty", span, vec![
+                    ("meta", self.identifier(meta)),
+                    ("property", self.identifier(property))
+                ])
+            }
+            Expression::Template(ref template) => self.template(span, template),
+            Expression::TaggedTemplate { ref tag, ref quasi } => {
+                self.node("TaggedTemplateExpression", span, vec![
+                    ("tag", self.expression(tag)),
+                    ("quasi", self.template(&quasi.span, &quasi.item))
+                ])
+            }
+            Expression::Sequence(ref expressions) => {
+                self.node("SequenceExpression", span, vec![("expressions", self.expressions(expressions))])
+            }
+            Expression::Spread(ref argument) => {
+                self.node("SpreadElement", span, vec![("argument", self.expression(argument))])
+            }
+            Expression::Yield { ref argument, delegate } => {
+                self.node("YieldExpression", span, vec![
+                    ("argument", option(argument.as_ref().map(|argument| self.expression(argument)))),
+                    ("delegate", boolean(delegate))
+                ])
+            }
+            Expression::Await(ref argument) => {
+                self.node("AwaitExpression", span, vec![("argument", self.expression(argument))])
+            }
+        }
+    }
+
+    fn template(&self, span: &Span, template: &TemplateLiteral) -> String {
+        let quasis = template.quasis.iter().map(|quasi| {
+            let value = format!("{{\"raw\":{},\"cooked\":{}}}", string(&quasi.item.raw), option(quasi.item.cooked.as_ref().map(|cooked| string(cooked))));
+            self.node("TemplateElement", &quasi.span, vec![
+                ("value", value),
+                ("tail", boolean(quasi.item.tail))
+            ])
+        }).collect();
+        self.node("TemplateLiteral", span, vec![
+            ("quasis", array(quasis)),
+            ("expressions", self.expressions(&template.expressions))
+        ])
+    }
+
+    fn patterns(&self, patterns: &[Node<Pattern>]) -> String {
+        array(patterns.iter().map(|pattern| self.pattern(pattern)).collect())
+    }
+
+    fn pattern(&self, pattern: &Node<Pattern>) -> String {
+        let span = &pattern.span;
+        match pattern.item {
+            Pattern::Identifier(ref name) => self.node("Identifier", span, vec![("name", string(name))]),
+            Pattern::Object(ref properties) => {
+                let properties = properties.iter().map(|property| match property.item {
+                    PatternProperty::Property { ref key, ref value, shorthand } => {
+                        let (key, computed) = self.property_key(key);
+                        self.node("Property", &property.span, vec![
+                            ("key", key),
+                            ("value", self.pattern(value)),
+                            ("kind", string("init")),
+                            ("method", boolean(false)),
+                            ("shorthand", boolean(shorthand)),
+                            ("computed", boolean(computed))
+                        ])
+                    }
+                    PatternProperty::Rest(ref argument) => {
+                        self.node("RestElement", &property.span, vec![("argument", self.pattern(argument))])
+                    }
+                }).collect();
+                self.node("ObjectPattern", span, vec![("properties", array(properties))])
+            }
+            Pattern::Array(ref elements) => {
+                let elements = elements.iter().map(|element| option(element.as_ref().map(|element| self.pattern(element)))).collect();
+                self.node("ArrayPattern", span, vec![("elements", array(elements))])
+            }
+            Pattern::Rest(ref argument) => {
+                self.node("RestElement", span, vec![("argument", self.pattern(argument))])
+            }
+            Pattern::Assignment { ref left, ref right } => {
+                self.node("AssignmentPattern", span, vec![
+                    ("left", self.pattern(left)),
+                    ("right", self.expression(right))
+                ])
+            }
+            Pattern::Expression(ref expr) => self.expression(expr)
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use lexer::enums::{LiteralType, SourceType};
+use lexer::token::{Span, Comment};
+
+/// A syntax tree item together with the source range it was parsed from.
+///
+/// Spans are location metadata only, two nodes are equal when their items are.
+#[derive(Debug, Clone)]
+pub struct Node<T> {
+    pub span: Span,
+    pub item: T
+}
+
+impl<T> Node<T> {
+    pub fn new(span: Span, item: T) -> Node<T> {
+        Node {
+            span: span,
+            item: item
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Node<T> {
+    fn eq(&self, other: &Node<T>) -> bool {
+        self.item == other.item
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Program {
+    pub body: Vec<Node<Statement>>,
+    /// Every comment in the source, in order.
+    pub comments: Vec<Comment<'static>>,
+    /// Whether the source was parsed as a script or a module.
+    pub source_type: SourceType
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Statement {
+    Empty,
+    Debugger,
+    Expression(Node<Expression>),
+    Block(Vec<Node<Statement>>),
+    If {
+        test: Node<Expression>,
+        consequent: Box<Node<Statement>>,
+        alternate: Option<Box<Node<Statement>>>
+    },
+    Labeled {
+        label: Node<String>,
+        body: Box<Node<Statement>>
+    },
+    Break(Option<Node<String>>),
+    Continue(Option<Node<String>>),
+    With {
+        object: Node<Expression>,
+        body: Box<Node<Statement>>
+    },
+    Switch {
+        discriminant: Node<Expression>,
+        cases: Vec<Node<SwitchCase>>
+    },
+    Return(Option<Node<Expression>>),
+    Throw(Node<Expression>),
+    Try {
+        block: Node<Vec<Node<Statement>>>,
+        handler: Option<Node<CatchClause>>,
+        finalizer: Option<Node<Vec<Node<Statement>>>>
+    },
+    While {
+        test: Node<Expression>,
+        body: Box<Node<Statement>>
+    },
+    DoWhile {
+        body: Box<Node<Statement>>,
+        test: Node<Expression>
+    },
+    For {
+        init: Option<ForInit>,
+        test: Option<Node<Expression>>,
+        update: Option<Node<Expression>>,
+        body: Box<Node<Statement>>
+    },
+    ForIn {
+        left: ForTarget,
+        right: Node<Expression>,
+        body: Box<Node<Statement>>
+    },
+    ForOf {
+        left: ForTarget,
+        right: Node<Expression>,
+        body: Box<Node<Statement>>,
+        /// `for await (... of ...)`
+        is_await: bool
+    },
+    Declaration(Declaration)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Declaration {
+    Function(Function),
+    Variable(VariableDeclaration),
+    Class(Class)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VariableKind {
+    Var,
+    Let,
+    Const
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct VariableDeclaration {
+    pub kind: VariableKind,
+    pub declarations: Vec<Node<VariableDeclarator>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct VariableDeclarator {
+    pub id: Node<Pattern>,
+    pub init: Option<Node<Expression>>
+}
+
+/// The first clause of a three-clause `for`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ForInit {
+    Variable(Node<VariableDeclaration>),
+    Expression(Node<Expression>)
+}
+
+/// What a `for-in` or `for-of` loop assigns each value to.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ForTarget {
+    Variable(Node<VariableDeclaration>),
+    Pattern(Node<Pattern>)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SwitchCase {
+    pub test: Option<Node<Expression>>,
+    pub consequent: Vec<Node<Statement>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CatchClause {
+    pub param: Node<Pattern>,
+    pub body: Node<Vec<Node<Statement>>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Function {
+    pub id: Option<Node<String>>,
+    pub params: Vec<Node<Pattern>>,
+    pub body: Node<Vec<Node<Statement>>>,
+    pub generator: bool,
+    pub is_async: bool
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Class {
+    pub id: Option<Node<String>>,
+    pub super_class: Option<Box<Node<Expression>>>,
+    pub body: Node<Vec<Node<ClassElement>>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ClassElement {
+    Method {
+        key: Node<PropertyKey>,
+        /// Always an `Expression::Function`.
+        value: Node<Expression>,
+        kind: MethodKind,
+        is_static: bool
+    },
+    Field {
+        key: Node<PropertyKey>,
+        value: Option<Node<Expression>>,
+        is_static: bool
+    },
+    StaticBlock(Vec<Node<Statement>>)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MethodKind {
+    Constructor,
+    Method,
+    Get,
+    Set
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArrowBody {
+    Block(Node<Vec<Node<Statement>>>),
+    Expression(Box<Node<Expression>>)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    This,
+    Super,
+    Identifier(String),
+    /// `#name` after a `.` or before `in`, without the `#`.
+    PrivateName(String),
+    Literal(LiteralType<'static>),
+    Array(Vec<Option<Node<Expression>>>),
+    Object(Vec<Node<Property>>),
+    Function(Function),
+    Class(Class),
+    Arrow {
+        params: Vec<Node<Pattern>>,
+        body: ArrowBody,
+        is_async: bool
+    },
+    Unary {
+        operator: UnaryOperator,
+        argument: Box<Node<Expression>>
+    },
+    Update {
+        operator: UpdateOperator,
+        prefix: bool,
+        argument: Box<Node<Expression>>
+    },
+    Binary {
+        operator: BinaryOperator,
+        left: Box<Node<Expression>>,
+        right: Box<Node<Expression>>
+    },
+    Logical {
+        operator: LogicalOperator,
+        left: Box<Node<Expression>>,
+        right: Box<Node<Expression>>
+    },
+    Assignment {
+        operator: AssignmentOperator,
+        left: Box<Node<Pattern>>,
+        right: Box<Node<Expression>>
+    },
+    Conditional {
+        test: Box<Node<Expression>>,
+        consequent: Box<Node<Expression>>,
+        alternate: Box<Node<Expression>>
+    },
+    Call {
+        callee: Box<Node<Expression>>,
+        arguments: Vec<Node<Expression>>
+    },
+    New {
+        callee: Box<Node<Expression>>,
+        arguments: Vec<Node<Expression>>
+    },
+    Member {
+        object: Box<Node<Expression>>,
+        property: Box<Node<Expression>>,
+        computed: bool
+    },
+    MetaProperty {
+        meta: Node<String>,
+        property: Node<String>
+    },
+    Template(TemplateLiteral),
+    TaggedTemplate {
+        tag: Box<Node<Expression>>,
+        quasi: Node<TemplateLiteral>
+    },
+    Sequence(Vec<Node<Expression>>),
+    Spread(Box<Node<Expression>>),
+    Yield {
+        argument: Option<Box<Node<Expression>>>,
+        delegate: bool
+    },
+    Await(Box<Node<Expression>>)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TemplateLiteral {
+    pub quasis: Vec<Node<TemplateElement>>,
+    pub expressions: Vec<Node<Expression>>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TemplateElement {
+    /// `None` for an invalid escape in a tagged template.
+    pub cooked: Option<String>,
+    pub raw: String,
+    pub tail: bool
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Pattern {
+    Identifier(String),
+    Object(Vec<Node<PatternProperty>>),
+    Array(Vec<Option<Node<Pattern>>>),
+    Rest(Box<Node<Pattern>>),
+    Assignment {
+        left: Box<Node<Pattern>>,
+        right: Box<Node<Expression>>
+    },
+    Expression(Box<Node<Expression>>)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PatternProperty {
+    Property {
+        key: Node<PropertyKey>,
+        value: Node<Pattern>,
+        shorthand: bool
+    },
+    Rest(Node<Pattern>)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PropertyKey {
+    Identifier(String),
+    Literal(LiteralType<'static>),
+    Computed(Node<Expression>),
+    /// `#name` in a class body, without the `#`.
+    Private(String)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PropertyKind {
+    Init,
+    Get,
+    Set
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Property {
+    pub key: Node<PropertyKey>,
+    pub value: Node<Expression>,
+    pub kind: PropertyKind,
+    pub method: bool,
+    pub shorthand: bool
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UnaryOperator {
+    Minus,
+    Plus,
+    Invert,
+    Tilde,
+    Typeof,
+    Void,
+    Delete
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UpdateOperator {
+    Increment,
+    Decrement
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BinaryOperator {
+    IsEqual,
+    IsNotEqual,
+    IsSame,
+    IsNotSame,
+    SmallThan,
+    SmallAndEqualThan,
+    GreaterThan,
+    GreaterAndEqualThan,
+    LeftShift,
+    RightShift,
+    RightShiftUnsigned,
+    Plus,
+    Minus,
+    Multiple,
+    Divide,
+    Mod,
+    Exp,
+    OrBitwise,
+    Xor,
+    AndBitwise,
+    In,
+    Instanceof
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LogicalOperator {
+    Or,
+    And
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AssignmentOperator {
+    Equal,
+    PlusAssign,
+    MinusAssign,
+    MultipleAssign,
+    DivideAssign,
+    ModAssign,
+    ExpAssign,
+    LeftShiftAssign,
+    RightShiftAssign,
+    RightShiftUnsignedAssign,
+    OrBitwiseAssign,
+    XorAssign,
+    AndBitwiseAssign
+}
@@ -1,12 +1,46 @@
 use lexer::enums::TokenType;
+use lexer::token::Span;
+use std::fmt;
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub enum SyntaxErrorType {
     UnexpectedEOF,
     UnexpectedEOL,
     UnexpectedChar(char),
-    Unexpected(TokenType),
-    MissingParameter(String)
+    Unexpected(TokenType<'static>),
+    InvalidAssignmentTarget,
+    InvalidUnicodeEscape,
+    InvalidNumber,
+    UnterminatedRegex,
+    UnterminatedString,
+    InvalidEscape,
+    InvalidRegexFlag(char),
+    MissingParameter(String),
+    /// A `const` or destructuring declaration without `= value`.
+    MissingInitializer,
+    /// A second `constructor`, or one that is a generator, async or an accessor.
+    InvalidConstructor,
+    DuplicatePrivateName(String),
+    UndeclaredPrivateName(String),
+    ReservedWord(String),
+    /// A parameter name bound twice where only sloppy functions with simple parameters allow it.
+    DuplicateParameter(String),
+    /// A static class member named `prototype`.
+    StaticPrototype,
+    /// `arguments` in a field initializer or a `static` block.
+    InvalidArguments,
+    /// `delete` of a private member like `this.#x`.
+    DeletePrivateName,
+    /// Syntax that strict code does not allow, like a legacy octal literal.
+    StrictMode(&'static str),
+    /// A `yield` or `await` expression in the parameters of a function.
+    ParameterExpression(&'static str),
+    /// A getter with parameters, or a setter without exactly one.
+    InvalidAccessor,
+    /// `super()` outside the constructor of a derived class, or `super.x` outside a method.
+    InvalidSuper,
+    /// `new.target` outside a function.
+    InvalidNewTarget
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
@@ -16,29 +50,99 @@ pub enum ErrorType {
 }
 
 pub trait CodePos {
-    fn location(&self) -> (u64, u32);
+    fn span(&self) -> Span;
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub struct Error {
     pub error_type: ErrorType,
-    line: u64,
-    col: u32,
-    expected: Option<&'static str>
+    span: Span,
+    expected: Vec<&'static str>
 }
 
 impl Error {
-    pub fn new(etype: ErrorType, col: u32, line: u64, expected: Option<&'static str>) -> Error {
+    pub fn new(etype: ErrorType, span: Span, expected: Vec<&'static str>) -> Error {
         Error {
             error_type: etype,
-            line: line,
-            col: col,
+            span: span,
             expected: expected
         }
     }
 
-    pub fn from_state<T>(etype: ErrorType, pos: &T, expected: Option<&'static str>) -> Error where T: CodePos {
-        let p = pos.location();
-        Error::new(etype, p.1, p.0, expected)
+    pub fn from_state<T>(etype: ErrorType, pos: &T, expected: Vec<&'static str>) -> Error where T: CodePos {
+        Error::new(etype, pos.span(), expected)
     }
-}
\ No newline at end of file
+
+    /// Line of the first char of the offending source, starting at 1.
+    pub fn line(&self) -> u64 {
+        self.span.start.line
+    }
+
+    /// Column of the first char of the offending source, starting at 1.
+    pub fn col(&self) -> u32 {
+        self.span.start.col
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Tokens or productions that would have been accepted where the error occurred.
+    pub fn expected(&self) -> &[&'static str] {
+        &self.expected
+    }
+}
+
+impl fmt::Display for SyntaxErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SyntaxErrorType::UnexpectedEOF => write!(f, "end of input"),
+            SyntaxErrorType::UnexpectedEOL => write!(f, "line terminator"),
+            SyntaxErrorType::UnexpectedChar(c) => write!(f, "{:?}", c),
+            SyntaxErrorType::Unexpected(ref t) => write!(f, "{}", t.description()),
+            SyntaxErrorType::InvalidAssignmentTarget => write!(f, "invalid assignment target"),
+            SyntaxErrorType::InvalidUnicodeEscape => write!(f, "invalid unicode escape sequence"),
+            SyntaxErrorType::InvalidNumber => write!(f, "invalid number"),
+            SyntaxErrorType::UnterminatedRegex => write!(f, "unterminated regular expression"),
+            SyntaxErrorType::UnterminatedString => write!(f, "unterminated string"),
+            SyntaxErrorType::InvalidEscape => write!(f, "invalid escape sequence"),
+            SyntaxErrorType::InvalidRegexFlag(c) => write!(f, "invalid regular expression flag {:?}", c),
+            SyntaxErrorType::MissingParameter(ref name) => write!(f, "missing parameter {}", name),
+            SyntaxErrorType::MissingInitializer => write!(f, "declaration without initializer"),
+            SyntaxErrorType::InvalidConstructor => write!(f, "invalid constructor"),
+            SyntaxErrorType::DuplicatePrivateName(ref name) => write!(f, "duplicate private name `#{}`", name),
+            SyntaxErrorType::UndeclaredPrivateName(ref name) => write!(f, "undeclared private name `#{}`", name),
+            SyntaxErrorType::ReservedWord(ref name) => write!(f, "reserved word `{}`", name),
+            SyntaxErrorType::DuplicateParameter(ref name) => write!(f, "duplicate parameter `{}`", name),
+            SyntaxErrorType::StaticPrototype => write!(f, "static member named `prototype`"),
+            SyntaxErrorType::InvalidArguments => write!(f, "`arguments` in a class field or static block"),
+            SyntaxErrorType::DeletePrivateName => write!(f, "deletion of a private member"),
+            SyntaxErrorType::StrictMode(what) => write!(f, "{} in strict mode", what),
+            SyntaxErrorType::ParameterExpression(keyword) => write!(f, "`{}` expression in parameters", keyword),
+            SyntaxErrorType::InvalidAccessor => write!(f, "wrong number of accessor parameters"),
+            SyntaxErrorType::InvalidSuper => write!(f, "`super` outside a method"),
+            SyntaxErrorType::InvalidNewTarget => write!(f, "`new.target` outside a function")
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.error_type {
+            // only a token that does not fit is described by what was expected instead
+            ErrorType::SyntaxError(ref error @ SyntaxErrorType::UnexpectedEOF) |
+            ErrorType::SyntaxError(ref error @ SyntaxErrorType::UnexpectedEOL) |
+            ErrorType::SyntaxError(ref error @ SyntaxErrorType::UnexpectedChar(_)) |
+            ErrorType::SyntaxError(ref error @ SyntaxErrorType::Unexpected(_)) => {
+                if self.expected.is_empty() {
+                    try!(write!(f, "unexpected {}", error));
+                } else {
+                    try!(write!(f, "expected {} but found {}", self.expected.join(" or "), error));
+                }
+            }
+            ErrorType::SyntaxError(ref error) => try!(write!(f, "{}", error)),
+            ErrorType::ReferenceError(ref name) => try!(write!(f, "{} is not defined", name))
+        }
+        write!(f, " at {}:{}", self.line(), self.col())
+    }
+}
@@ -1,20 +1,158 @@
+use std::borrow::Cow;
+
+fn owned(text: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
 #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
 pub enum NumberType {
+    /// After a leading `0`.
     None,
     NoneLiteral,
     Hex,
     Octal,
-    Float
+    Binary,
+    /// `017`, a leading `0` followed by octal digits.
+    LegacyOctal,
+    /// `089`, a leading `0` followed by decimal digits.
+    LegacyDecimal,
+    Float,
+    /// After the `e` of an exponent.
+    Exponent,
+    ExponentSign,
+    ExponentDigits,
+    /// After the `n` suffix, with the radix of the digits.
+    BigInt(u32)
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
 pub enum CommentType {
     SingleLine,
+    /// `#!` at the very start of the input.
+    Hashbang,
+    /// Annex B `<!--` or `-->`, only in scripts.
+    Html,
     MultiLineStart,
     MultiLineEnd,
     MultiLineNormal
 }
 
+/// Words lexed as identifiers that the parser treats as keywords in some contexts, or that are
+/// reserved in strict mode code or modules only.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum Contextual {
+    As,
+    Async,
+    Await,
+    From,
+    Get,
+    Implements,
+    Interface,
+    Let,
+    Of,
+    Package,
+    Private,
+    Protected,
+    Public,
+    Set,
+    Static,
+    Target,
+    Yield
+}
+
+impl Contextual {
+    pub fn from_name(name: &str) -> Option<Contextual> {
+        let c = match name {
+            "as" => Contextual::As,
+            "async" => Contextual::Async,
+            "await" => Contextual::Await,
+            "from" => Contextual::From,
+            "get" => Contextual::Get,
+            "implements" => Contextual::Implements,
+            "interface" => Contextual::Interface,
+            "let" => Contextual::Let,
+            "of" => Contextual::Of,
+            "package" => Contextual::Package,
+            "private" => Contextual::Private,
+            "protected" => Contextual::Protected,
+            "public" => Contextual::Public,
+            "set" => Contextual::Set,
+            "static" => Contextual::Static,
+            "target" => Contextual::Target,
+            "yield" => Contextual::Yield,
+            _ => return None
+        };
+        Some(c)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Contextual::As => "as",
+            Contextual::Async => "async",
+            Contextual::Await => "await",
+            Contextual::From => "from",
+            Contextual::Get => "get",
+            Contextual::Implements => "implements",
+            Contextual::Interface => "interface",
+            Contextual::Let => "let",
+            Contextual::Of => "of",
+            Contextual::Package => "package",
+            Contextual::Private => "private",
+            Contextual::Protected => "protected",
+            Contextual::Public => "public",
+            Contextual::Set => "set",
+            Contextual::Static => "static",
+            Contextual::Target => "target",
+            Contextual::Yield => "yield"
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match *self {
+            Contextual::As => "`as`",
+            Contextual::Async => "`async`",
+            Contextual::Await => "`await`",
+            Contextual::From => "`from`",
+            Contextual::Get => "`get`",
+            Contextual::Implements => "`implements`",
+            Contextual::Interface => "`interface`",
+            Contextual::Let => "`let`",
+            Contextual::Of => "`of`",
+            Contextual::Package => "`package`",
+            Contextual::Private => "`private`",
+            Contextual::Protected => "`protected`",
+            Contextual::Public => "`public`",
+            Contextual::Set => "`set`",
+            Contextual::Static => "`static`",
+            Contextual::Target => "`target`",
+            Contextual::Yield => "`yield`"
+        }
+    }
+
+    /// Reserved in strict mode code, and so in modules.
+    pub fn is_strict_reserved(&self) -> bool {
+        match *self {
+            Contextual::Implements |
+            Contextual::Interface |
+            Contextual::Let |
+            Contextual::Package |
+            Contextual::Private |
+            Contextual::Protected |
+            Contextual::Public |
+            Contextual::Static |
+            Contextual::Yield => true,
+            _ => false
+        }
+    }
+}
+
+/// Whether the input is a script or a module.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum SourceType {
+    Script,
+    Module
+}
+
 #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
 pub enum StringType {
     SingleQuote,
@@ -24,7 +162,26 @@ pub enum StringType {
 #[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
 pub enum RegexState {
     Normal,
-    Identifier
+    /// Inside a `[...]` class, where `/` does not end the body.
+    Class,
+    Flags
+}
+
+/// Lexical goal of the next token, it decides whether a `/` starts a regex or is a division.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum Goal {
+    /// InputElementDiv
+    Div,
+    /// InputElementRegExp
+    RegExp
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum TemplateType {
+    /// Opened by a backtick.
+    Head,
+    /// Reopened by the `}` closing a substitution.
+    Middle
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
@@ -32,35 +189,76 @@ pub enum LexerMode {
     None,
     String(StringType),
     Number(NumberType),
-    Punctuator(TokenType, i32),
+    Punctuator(TokenType<'static>, i32),
     Comment(CommentType),
-    Raw,
+    /// The bool is set once the identifier contains an escape.
+    Raw(bool),
     Regex(RegexState),
+    /// The bool is set after a `$` that may start a substitution.
+    Template(TemplateType, bool),
     EOF
 }
 
+/// Text borrows from the source where it is written out verbatim, only decoded escapes allocate.
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
-pub enum LiteralType {
-    String(String),
-    Regex(String, RegexIdentifier),
+pub enum LiteralType<'a> {
+    /// Value with the escapes decoded, and the source text including the quotes.
+    String(Cow<'a, str>, Cow<'a, str>),
+    /// Pattern and flags, both as written in the source.
+    Regex(Cow<'a, str>, Cow<'a, str>),
     Integer(i64),
     Float(f64),
+    /// Decimal digits of the value.
+    BigInt(Cow<'a, str>),
     Boolean(bool),
     Null
 }
 
+impl<'a> LiteralType<'a> {
+    pub fn into_owned(self) -> LiteralType<'static> {
+        match self {
+            LiteralType::String(value, raw) => LiteralType::String(owned(value), owned(raw)),
+            LiteralType::Regex(pattern, flags) => LiteralType::Regex(owned(pattern), owned(flags)),
+            LiteralType::Integer(i) => LiteralType::Integer(i),
+            LiteralType::Float(f) => LiteralType::Float(f),
+            LiteralType::BigInt(digits) => LiteralType::BigInt(owned(digits)),
+            LiteralType::Boolean(b) => LiteralType::Boolean(b),
+            LiteralType::Null => LiteralType::Null
+        }
+    }
+}
+
+/// The text of a template part, with its escape sequences evaluated (`cooked`) and as written (`raw`).
+/// `cooked` is `None` when an escape is invalid, which only tagged templates allow.
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
-pub enum RegexIdentifier {
-    Global,
-    Ignore,
-    None
+pub struct TemplateString<'a> {
+    pub cooked: Option<Cow<'a, str>>,
+    pub raw: Cow<'a, str>
+}
+
+impl<'a> TemplateString<'a> {
+    pub fn into_owned(self) -> TemplateString<'static> {
+        TemplateString {
+            cooked: self.cooked.map(owned),
+            raw: owned(self.raw)
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
-pub enum TokenType {
-    Literal(LiteralType),
-    CommentLiteral(String),
-    Identifier(String),
+pub enum TokenType<'a> {
+    Literal(LiteralType<'a>),
+    NoSubstitutionTemplate(TemplateString<'a>),
+    // `...`
+    TemplateHead(TemplateString<'a>),
+    // `...${
+    TemplateMiddle(TemplateString<'a>),
+    // }...${
+    TemplateTail(TemplateString<'a>),
+    // }...`
+    Identifier(Cow<'a, str>),
+    /// `#name` in a class body, without the `#`.
+    PrivateName(Cow<'a, str>),
     Plus,
     // +
     Minus,
@@ -123,6 +321,12 @@ pub enum TokenType {
     // >>>
     QuestionMark,
     // ?
+    OptionalChaining,
+    // ?.
+    Nullish,
+    // ??
+    NullishAssign,
+    // ??=
     Tilde,
     //~
     Mod,
@@ -139,6 +343,8 @@ pub enum TokenType {
     // |=
     Or,
     // ||
+    OrAssign,
+    // ||=
     Multiple,
     // *
     MultipleAssign,
@@ -149,6 +355,8 @@ pub enum TokenType {
     // &=
     And,
     // &&
+    AndAssign,
+    // &&=
     Exp,
     // **
     ExpAssign,
@@ -179,10 +387,8 @@ pub enum TokenType {
     Super,
     With,
     Delete,
-    Yield,
     Default,
     Function,
-    Of,
     In,
     For,
     While,
@@ -191,14 +397,273 @@ pub enum TokenType {
     Break,
     Continue,
     New,
-    Let,
     Throw,
     Debugger,
     This,
-    Target,
+    Import,
+    Enum,
+    /// An unescaped identifier that is a keyword in some contexts.
+    Contextual(Contextual),
     Semicolon,
-    Comma,
-    Get,
-    Set,
-    LineTerminate
-}
\ No newline at end of file
+    Comma
+}
+impl<'a> TokenType<'a> {
+    /// The token for a reserved word, `None` for any other identifier name.
+    pub fn keyword(name: &str) -> Option<TokenType<'static>> {
+        let token = match name {
+            "var" => TokenType::Var,
+            "if" => TokenType::If,
+            "else" => TokenType::Else,
+            "do" => TokenType::Do,
+            "typeof" => TokenType::Typeof,
+            "switch" => TokenType::Switch,
+            "catch" => TokenType::Catch,
+            "try" => TokenType::Try,
+            "instanceof" => TokenType::Instanceof,
+            "export" => TokenType::Export,
+            "return" => TokenType::Return,
+            "void" => TokenType::Void,
+            "extends" => TokenType::Extends,
+            "const" => TokenType::Const,
+            "finally" => TokenType::Finally,
+            "super" => TokenType::Super,
+            "with" => TokenType::With,
+            "default" => TokenType::Default,
+            "function" => TokenType::Function,
+            "in" => TokenType::In,
+            "for" => TokenType::For,
+            "while" => TokenType::While,
+            "class" => TokenType::Class,
+            "break" => TokenType::Break,
+            "continue" => TokenType::Continue,
+            "new" => TokenType::New,
+            "case" => TokenType::Case,
+            "debugger" => TokenType::Debugger,
+            "throw" => TokenType::Throw,
+            "this" => TokenType::This,
+            "delete" => TokenType::Delete,
+            "import" => TokenType::Import,
+            "enum" => TokenType::Enum,
+            "true" => TokenType::Literal(LiteralType::Boolean(true)),
+            "false" => TokenType::Literal(LiteralType::Boolean(false)),
+            "null" => TokenType::Literal(LiteralType::Null),
+            _ => return None
+        };
+        Some(token)
+    }
+
+    /// Copies any borrowed text, so the token can outlive the source.
+    pub fn into_owned(self) -> TokenType<'static> {
+        match self {
+            TokenType::Literal(l) => TokenType::Literal(l.into_owned()),
+            TokenType::NoSubstitutionTemplate(s) => TokenType::NoSubstitutionTemplate(s.into_owned()),
+            TokenType::TemplateHead(s) => TokenType::TemplateHead(s.into_owned()),
+            TokenType::TemplateMiddle(s) => TokenType::TemplateMiddle(s.into_owned()),
+            TokenType::TemplateTail(s) => TokenType::TemplateTail(s.into_owned()),
+            TokenType::Identifier(name) => TokenType::Identifier(owned(name)),
+            TokenType::PrivateName(name) => TokenType::PrivateName(owned(name)),
+            TokenType::Contextual(c) => TokenType::Contextual(c),
+            TokenType::Plus => TokenType::Plus,
+            TokenType::Minus => TokenType::Minus,
+            TokenType::PlusAssign => TokenType::PlusAssign,
+            TokenType::MinusAssign => TokenType::MinusAssign,
+            TokenType::Divide => TokenType::Divide,
+            TokenType::DivideAssign => TokenType::DivideAssign,
+            TokenType::SmallThan => TokenType::SmallThan,
+            TokenType::GreaterThan => TokenType::GreaterThan,
+            TokenType::SmallAndEqualThan => TokenType::SmallAndEqualThan,
+            TokenType::GreaterAndEqualThan => TokenType::GreaterAndEqualThan,
+            TokenType::RightBrace => TokenType::RightBrace,
+            TokenType::LeftBrace => TokenType::LeftBrace,
+            TokenType::RightBracket => TokenType::RightBracket,
+            TokenType::LeftBracket => TokenType::LeftBracket,
+            TokenType::RightParen => TokenType::RightParen,
+            TokenType::LeftParen => TokenType::LeftParen,
+            TokenType::Point => TokenType::Point,
+            TokenType::Colon => TokenType::Colon,
+            TokenType::Equal => TokenType::Equal,
+            TokenType::IsEqual => TokenType::IsEqual,
+            TokenType::IsNotEqual => TokenType::IsNotEqual,
+            TokenType::IsSame => TokenType::IsSame,
+            TokenType::IsNotSame => TokenType::IsNotSame,
+            TokenType::Increment => TokenType::Increment,
+            TokenType::Decrement => TokenType::Decrement,
+            TokenType::LeftShift => TokenType::LeftShift,
+            TokenType::RightShift => TokenType::RightShift,
+            TokenType::Invert => TokenType::Invert,
+            TokenType::Lamda => TokenType::Lamda,
+            TokenType::RightShiftUnsigned => TokenType::RightShiftUnsigned,
+            TokenType::QuestionMark => TokenType::QuestionMark,
+            TokenType::OptionalChaining => TokenType::OptionalChaining,
+            TokenType::Nullish => TokenType::Nullish,
+            TokenType::NullishAssign => TokenType::NullishAssign,
+            TokenType::Tilde => TokenType::Tilde,
+            TokenType::Mod => TokenType::Mod,
+            TokenType::ModAssign => TokenType::ModAssign,
+            TokenType::Xor => TokenType::Xor,
+            TokenType::XorAssign => TokenType::XorAssign,
+            TokenType::OrBitwise => TokenType::OrBitwise,
+            TokenType::OrBitwiseAssign => TokenType::OrBitwiseAssign,
+            TokenType::Or => TokenType::Or,
+            TokenType::OrAssign => TokenType::OrAssign,
+            TokenType::Multiple => TokenType::Multiple,
+            TokenType::MultipleAssign => TokenType::MultipleAssign,
+            TokenType::AndBitwise => TokenType::AndBitwise,
+            TokenType::AndBitwiseAssign => TokenType::AndBitwiseAssign,
+            TokenType::And => TokenType::And,
+            TokenType::AndAssign => TokenType::AndAssign,
+            TokenType::Exp => TokenType::Exp,
+            TokenType::ExpAssign => TokenType::ExpAssign,
+            TokenType::LeftShiftAssign => TokenType::LeftShiftAssign,
+            TokenType::RightShiftAssign => TokenType::RightShiftAssign,
+            TokenType::ThreePoints => TokenType::ThreePoints,
+            TokenType::RightShiftUnsignedAssign => TokenType::RightShiftUnsignedAssign,
+            TokenType::Var => TokenType::Var,
+            TokenType::If => TokenType::If,
+            TokenType::Else => TokenType::Else,
+            TokenType::Do => TokenType::Do,
+            TokenType::Typeof => TokenType::Typeof,
+            TokenType::Switch => TokenType::Switch,
+            TokenType::Catch => TokenType::Catch,
+            TokenType::Try => TokenType::Try,
+            TokenType::Instanceof => TokenType::Instanceof,
+            TokenType::Export => TokenType::Export,
+            TokenType::Return => TokenType::Return,
+            TokenType::Void => TokenType::Void,
+            TokenType::Extends => TokenType::Extends,
+            TokenType::Const => TokenType::Const,
+            TokenType::Finally => TokenType::Finally,
+            TokenType::Super => TokenType::Super,
+            TokenType::With => TokenType::With,
+            TokenType::Delete => TokenType::Delete,
+            TokenType::Default => TokenType::Default,
+            TokenType::Function => TokenType::Function,
+            TokenType::In => TokenType::In,
+            TokenType::For => TokenType::For,
+            TokenType::While => TokenType::While,
+            TokenType::Class => TokenType::Class,
+            TokenType::Case => TokenType::Case,
+            TokenType::Break => TokenType::Break,
+            TokenType::Continue => TokenType::Continue,
+            TokenType::New => TokenType::New,
+            TokenType::Throw => TokenType::Throw,
+            TokenType::Debugger => TokenType::Debugger,
+            TokenType::This => TokenType::This,
+            TokenType::Import => TokenType::Import,
+            TokenType::Enum => TokenType::Enum,
+            TokenType::Semicolon => TokenType::Semicolon,
+            TokenType::Comma => TokenType::Comma,
+        }
+    }
+
+    /// Short human readable form of the token, used in error messages.
+    pub fn description(&self) -> &'static str {
+        match *self {
+            TokenType::Literal(LiteralType::String(_, _)) => "string",
+            TokenType::Literal(LiteralType::Regex(_, _)) => "regular expression",
+            TokenType::Literal(LiteralType::Integer(_)) |
+            TokenType::Literal(LiteralType::Float(_)) |
+            TokenType::Literal(LiteralType::BigInt(_)) => "number",
+            TokenType::Literal(LiteralType::Boolean(true)) => "`true`",
+            TokenType::Literal(LiteralType::Boolean(false)) => "`false`",
+            TokenType::Literal(LiteralType::Null) => "`null`",
+            TokenType::NoSubstitutionTemplate(_) |
+            TokenType::TemplateHead(_) => "template",
+            TokenType::TemplateMiddle(_) |
+            TokenType::TemplateTail(_) => "template continuation",
+            TokenType::Identifier(_) => "identifier",
+            TokenType::PrivateName(_) => "private name",
+            TokenType::Plus => "`+`",
+            TokenType::Minus => "`-`",
+            TokenType::PlusAssign => "`+=`",
+            TokenType::MinusAssign => "`-=`",
+            TokenType::Divide => "`/`",
+            TokenType::DivideAssign => "`/=`",
+            TokenType::SmallThan => "`<`",
+            TokenType::GreaterThan => "`>`",
+            TokenType::SmallAndEqualThan => "`<=`",
+            TokenType::GreaterAndEqualThan => "`>=`",
+            TokenType::RightBrace => "`}`",
+            TokenType::LeftBrace => "`{`",
+            TokenType::RightBracket => "`]`",
+            TokenType::LeftBracket => "`[`",
+            TokenType::RightParen => "`)`",
+            TokenType::LeftParen => "`(`",
+            TokenType::Point => "`.`",
+            TokenType::Colon => "`:`",
+            TokenType::Equal => "`=`",
+            TokenType::IsEqual => "`==`",
+            TokenType::IsNotEqual => "`!=`",
+            TokenType::IsSame => "`===`",
+            TokenType::IsNotSame => "`!==`",
+            TokenType::Increment => "`++`",
+            TokenType::Decrement => "`--`",
+            TokenType::LeftShift => "`<<`",
+            TokenType::RightShift => "`>>`",
+            TokenType::Invert => "`!`",
+            TokenType::Lamda => "`=>`",
+            TokenType::RightShiftUnsigned => "`>>>`",
+            TokenType::QuestionMark => "`?`",
+            TokenType::OptionalChaining => "`?.`",
+            TokenType::Nullish => "`??`",
+            TokenType::NullishAssign => "`??=`",
+            TokenType::Tilde => "`~`",
+            TokenType::Mod => "`%`",
+            TokenType::ModAssign => "`%=`",
+            TokenType::Xor => "`^`",
+            TokenType::XorAssign => "`^=`",
+            TokenType::OrBitwise => "`|`",
+            TokenType::OrBitwiseAssign => "`|=`",
+            TokenType::Or => "`||`",
+            TokenType::OrAssign => "`||=`",
+            TokenType::Multiple => "`*`",
+            TokenType::MultipleAssign => "`*=`",
+            TokenType::AndBitwise => "`&`",
+            TokenType::AndBitwiseAssign => "`&=`",
+            TokenType::And => "`&&`",
+            TokenType::AndAssign => "`&&=`",
+            TokenType::Exp => "`**`",
+            TokenType::ExpAssign => "`**=`",
+            TokenType::LeftShiftAssign => "`<<=`",
+            TokenType::RightShiftAssign => "`>>=`",
+            TokenType::ThreePoints => "`...`",
+            TokenType::RightShiftUnsignedAssign => "`>>>=`",
+            TokenType::Var => "`var`",
+            TokenType::If => "`if`",
+            TokenType::Else => "`else`",
+            TokenType::Do => "`do`",
+            TokenType::Typeof => "`typeof`",
+            TokenType::Switch => "`switch`",
+            TokenType::Catch => "`catch`",
+            TokenType::Try => "`try`",
+            TokenType::Instanceof => "`instanceof`",
+            TokenType::Export => "`export`",
+            TokenType::Return => "`return`",
+            TokenType::Void => "`void`",
+            TokenType::Extends => "`extends`",
+            TokenType::Const => "`const`",
+            TokenType::Finally => "`finally`",
+            TokenType::Super => "`super`",
+            TokenType::With => "`with`",
+            TokenType::Delete => "`delete`",
+            TokenType::Default => "`default`",
+            TokenType::Function => "`function`",
+            TokenType::In => "`in`",
+            TokenType::For => "`for`",
+            TokenType::While => "`while`",
+            TokenType::Class => "`class`",
+            TokenType::Case => "`case`",
+            TokenType::Break => "`break`",
+            TokenType::Continue => "`continue`",
+            TokenType::New => "`new`",
+            TokenType::Throw => "`throw`",
+            TokenType::Debugger => "`debugger`",
+            TokenType::This => "`this`",
+            TokenType::Import => "`import`",
+            TokenType::Enum => "`enum`",
+            TokenType::Contextual(c) => c.description(),
+            TokenType::Semicolon => "`;`",
+            TokenType::Comma => "`,`"
+        }
+    }
+}
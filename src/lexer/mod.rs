@@ -2,3 +2,4 @@ mod mode;
 pub mod enums;
 pub mod state;
 pub mod token;
+pub mod unicode;
@@ -1,13 +1,40 @@
 use error::JsResult;
-use lexer::enums::{LexerMode, CommentType, TokenType};
+use lexer::enums::{LexerMode, CommentType, SourceType};
+use lexer::token::CommentKind;
 use lexer::state::{LexerState};
+use lexer::unicode::is_line_terminator;
 
-impl LexerState {
-    fn comment(&mut self) -> JsResult<()> {
-        let tmp = self.tmp();
-        try!(self.push(TokenType::CommentLiteral(tmp)));
-        self.update(LexerMode::None);
-        Ok(())
+impl<'a> LexerState<'a> {
+    fn comment(&mut self, t: CommentType) {
+        let kind = match t {
+            CommentType::SingleLine => CommentKind::Line,
+            CommentType::Hashbang => CommentKind::Hashbang,
+            CommentType::Html => CommentKind::Html,
+            _ => CommentKind::Block
+        };
+        self.push_comment(kind);
+    }
+
+    pub fn start_comment(&mut self, t: CommentType) {
+        self.update(LexerMode::Comment(t));
+        self.reset_tmp();
+    }
+
+    /// Whether `#!` starts a hashbang comment here.
+    pub fn at_hashbang(&mut self) -> bool {
+        self.at_input_start() && self.peek(0) == Some('!')
+    }
+
+    /// Whether `<!--` starts an Annex B comment here.
+    pub fn at_html_open_comment(&mut self) -> bool {
+        self.source_type() == SourceType::Script &&
+            self.peek(0) == Some('!') && self.peek(1) == Some('-') && self.peek(2) == Some('-')
+    }
+
+    /// Whether `-->` starts an Annex B comment here, only allowed at the start of a line.
+    pub fn at_html_close_comment(&mut self) -> bool {
+        self.source_type() == SourceType::Script && self.at_line_start() &&
+            self.peek(0) == Some('-') && self.peek(1) == Some('>')
     }
 
     pub fn parse_comment(&mut self) -> JsResult<bool> {
@@ -15,46 +42,35 @@ impl LexerState {
             let c = self.current_char();
             let t = match self.mode() {
                 LexerMode::Comment(t) => t,
-                _ => {
-                    panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line())
-                }
+                _ => break
             };
             match (c, t) {
-                (Some('\n'), CommentType::SingleLine) => {
-                    try!(self.comment());
-                    try!(self.push(TokenType::LineTerminate));
+                (Some(c), CommentType::SingleLine) |
+                (Some(c), CommentType::Hashbang) |
+                (Some(c), CommentType::Html) => {
+                    if is_line_terminator(c) {
+                        self.comment(t);
+                        self.update(LexerMode::None);
+                        return Ok(false)
+                    }
+                    self.tmp_push(c);
                 }
                 (Some('/'), CommentType::MultiLineEnd) => {
-                    try!(self.comment());
-                }
-                (Some('*'), CommentType::MultiLineStart) => {
-                    self.update(LexerMode::Comment(CommentType::MultiLineEnd));
-                }
-                (Some('*'), CommentType::SingleLine) => {
-                    self.tmp_push(c.unwrap());
-                }
-                (Some('*'), CommentType::MultiLineEnd) => {
-                    self.tmp_push(c.unwrap());
-                    self.update(LexerMode::Comment(CommentType::MultiLineEnd));
+                    // the `*` before it belongs to the delimiter
+                    self.tmp_pop();
+                    self.comment(t);
+                    self.update(LexerMode::None);
                 }
                 (Some('*'), _) => {
-                    self.update(LexerMode::Comment(CommentType::MultiLineEnd));
-                }
-                (Some(c), CommentType::MultiLineEnd) => {
                     self.tmp_push('*');
-                    self.tmp_push(c);
-                    self.update(LexerMode::Comment(CommentType::MultiLineNormal));
-                }
-                (Some(c), CommentType::SingleLine) => {
-                    self.tmp_push(c);
+                    self.update(LexerMode::Comment(CommentType::MultiLineEnd));
                 }
                 (Some(c), _) => {
                     self.tmp_push(c);
                     self.update(LexerMode::Comment(CommentType::MultiLineNormal));
                 }
                 (None, _) => {
-                    let tmp = self.tmp();
-                    try!(self.push(TokenType::CommentLiteral(tmp)));
+                    self.comment(t);
                     self.update(LexerMode::EOF);
                 }
             };
@@ -65,4 +81,4 @@ impl LexerState {
         }
         Ok(true)
     }
-}
\ No newline at end of file
+}
@@ -4,4 +4,5 @@ pub mod comment;
 pub mod punctuator;
 pub mod raw;
 pub mod string;
-pub mod regex;
\ No newline at end of file
+pub mod regex;
+pub mod template;
\ No newline at end of file
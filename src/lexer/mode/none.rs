@@ -1,20 +1,22 @@
 use error::JsResult;
 use error::error::{ErrorType, SyntaxErrorType};
-use lexer::enums::{LexerMode, TokenType, NumberType, StringType};
+use lexer::enums::{LexerMode, TokenType, NumberType, StringType, TemplateType, CommentType};
 use lexer::state::{LexerState};
+use lexer::unicode::{is_identifier_start, is_line_terminator, is_whitespace};
 
-impl LexerState {
-    fn start_punctuator(&mut self, t: TokenType) {
+impl<'a> LexerState<'a> {
+    fn start_punctuator(&mut self, t: TokenType<'static>) {
         self.update(LexerMode::Punctuator(t, 0));
     }
 
     pub fn parse_normal(&mut self, c: Option<char>) -> JsResult<bool> {
         let mut handled = true;
         match c {
-            Some('a' ... 'z') | Some('A' ... 'Z') | Some('_') | Some('$') => {
-                self.update(LexerMode::Raw);
+            Some(c) if is_identifier_start(c) => {
+                let escaped = self.last_char_is_unicode();
+                self.update(LexerMode::Raw(escaped));
                 self.reset_tmp();
-                self.tmp_push(c.unwrap());
+                self.tmp_push(c);
             }
             Some('"') => {
                 self.update(LexerMode::String(StringType::DoubleQuote));
@@ -34,39 +36,56 @@ impl LexerState {
                 self.reset_tmp();
                 self.tmp_push(c.unwrap());
             }
-            Some('\n') |
-            Some('\r') => try!(self.push(TokenType::LineTerminate)),
-            Some(' ') |
-            Some('\t') |
-            Some('\u{c}') |
-            Some('\u{b}') |
-            Some('\u{a0}') => {
-                if self.last_char_is_unicode() {
-                    let c = self.current_char();
-                    let err = self.error(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar(c.unwrap())));
-                    return Err(err);
-                }
-            },
+            Some('\n') if self.last_char() == Some('\r') => (),
+            Some(c) if is_line_terminator(c) => self.line_break(),
+            Some(c) if is_whitespace(c) => (),
+            Some('#') if self.at_hashbang() => {
+                self.skip_chars(1);
+                self.start_comment(CommentType::Hashbang)
+            }
+            Some('#') if self.at_private_name() => {
+                self.update(LexerMode::Raw(false));
+                self.reset_tmp();
+                self.tmp_push('#');
+            }
             Some(';') => try!(self.push(TokenType::Semicolon)),
             Some(',') => try!(self.push(TokenType::Comma)),
-            Some('{') => try!(self.push(TokenType::LeftBrace)),
-            Some('}') => try!(self.push(TokenType::RightBrace)),
+            Some('`') => self.start_template(TemplateType::Head),
+            Some('{') => {
+                self.open_brace();
+                try!(self.push(TokenType::LeftBrace))
+            }
+            Some('}') => {
+                if self.close_brace() {
+                    self.start_template(TemplateType::Middle)
+                } else {
+                    try!(self.push(TokenType::RightBrace))
+                }
+            }
             Some('[') => try!(self.push(TokenType::LeftBracket)),
             Some(']') => try!(self.push(TokenType::RightBracket)),
             Some('(') => try!(self.push(TokenType::LeftParen)),
             Some(')') => try!(self.push(TokenType::RightParen)),
             Some('~') => try!(self.push(TokenType::Tilde)),
             Some(':') => try!(self.push(TokenType::Colon)),
-            Some('?') => try!(self.push(TokenType::QuestionMark)),
+            Some('?') => self.start_punctuator(TokenType::QuestionMark),
             Some('.') => self.start_punctuator(TokenType::Point),
             Some('|') => self.start_punctuator(TokenType::OrBitwise),
             Some('*') => self.start_punctuator(TokenType::Multiple),
             Some('&') => self.start_punctuator(TokenType::AndBitwise),
             Some('^') => self.start_punctuator(TokenType::Xor),
             Some('+') => self.start_punctuator(TokenType::Plus),
+            Some('-') if self.at_html_close_comment() => {
+                self.skip_chars(2);
+                self.start_comment(CommentType::Html)
+            }
             Some('-') => self.start_punctuator(TokenType::Minus),
             Some('%') => self.start_punctuator(TokenType::Mod),
             Some('=') => self.start_punctuator(TokenType::Equal),
+            Some('<') if self.at_html_open_comment() => {
+                self.skip_chars(3);
+                self.start_comment(CommentType::Html)
+            }
             Some('<') => self.start_punctuator(TokenType::SmallThan),
             Some('/') => self.start_punctuator(TokenType::Divide),
             Some('!') => self.start_punctuator(TokenType::Invert),
@@ -75,21 +94,15 @@ impl LexerState {
                 self.update(LexerMode::EOF)
             }
             Some('\\') => {
-                let unicode = self.read_unicode();
-                match unicode {
-                    Some(c) => {
-                        println!("{:?}", c);
-                        self.overwrite_current_char_with_unicode(c);
-                        handled = false
-                    }
-                    _ => {
-                        panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
-                    }
+                let c = try!(self.read_unicode());
+                if !is_identifier_start(c) {
+                    return Err(self.token_error(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)))
                 }
+                self.overwrite_current_char_with_unicode(c);
+                handled = false
             }
-            _ => {
-                panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
-                //self.update(LexerMode::EOF);
+            Some(c) => {
+                return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar(c))))
             }
         }
         Ok(handled)
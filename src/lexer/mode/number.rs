@@ -1,24 +1,114 @@
 use error::JsResult;
+use error::error::{ErrorType, SyntaxErrorType};
 use lexer::enums::{LexerMode, NumberType, TokenType, LiteralType};
 use lexer::state::{LexerState};
+use lexer::unicode::is_identifier_start;
+use std::borrow::Cow;
 use std::str::FromStr;
 
-impl LexerState {
-    fn number(&mut self, t: LiteralType) -> JsResult<()> {
-        try!(self.push(TokenType::Literal(t)));
+/// Largest integer an f64 holds exactly, literals above it are kept as floats.
+const MAX_SAFE_INTEGER: i64 = 9007199254740991;
+
+/// Converts `digits` in `radix` to base 10, so values of any size can be rounded once by the
+/// f64 parser or kept as a BigInt.
+fn to_decimal(digits: &str, radix: u32) -> String {
+    // little endian limbs of nine decimal digits each
+    let mut limbs: Vec<u64> = vec![0];
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix).unwrap_or(0) as u64;
+        for limb in limbs.iter_mut() {
+            let value = *limb * radix as u64 + carry;
+            *limb = value % 1_000_000_000;
+            carry = value / 1_000_000_000;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+    let mut out = String::new();
+    for (i, limb) in limbs.iter().rev().enumerate() {
+        if i == 0 {
+            out.push_str(&limb.to_string());
+        } else {
+            out.push_str(&format!("{:09}", limb));
+        }
+    }
+    out
+}
+
+/// The value of a decimal literal, integers stay exact while an f64 can hold them.
+fn decimal(text: &str) -> Option<LiteralType<'static>> {
+    if !text.contains('.') && !text.contains('e') {
+        match i64::from_str(text) {
+            Ok(i) if i <= MAX_SAFE_INTEGER => return Some(LiteralType::Integer(i)),
+            _ => ()
+        }
+    }
+    f64::from_str(text).ok().map(LiteralType::Float)
+}
+
+fn radix_of(t: NumberType) -> u32 {
+    match t {
+        NumberType::Hex => 16,
+        NumberType::Octal | NumberType::LegacyOctal => 8,
+        NumberType::Binary => 2,
+        NumberType::BigInt(radix) => radix,
+        _ => 10
+    }
+}
+
+impl<'a> LexerState<'a> {
+    fn number(&mut self, t: LiteralType<'a>) -> JsResult<()> {
+        try!(self.push_before(TokenType::Literal(t)));
         self.update(LexerMode::None);
         Ok(())
     }
 
+    fn invalid_number<T>(&mut self) -> JsResult<T> {
+        Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::InvalidNumber)))
+    }
+
+    /// A `_` separator has to stand between two digits.
+    fn after_separator(&self) -> bool {
+        self.last_char() == Some('_')
+    }
+
+    fn finish_number(&mut self, t: NumberType) -> JsResult<()> {
+        if self.after_separator() || self.tmp().is_empty() {
+            return self.invalid_number()
+        }
+        match self.current_char() {
+            Some(c) if is_identifier_start(c) || c.is_ascii_digit() || c == '\\' => {
+                return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar(c))))
+            }
+            _ => ()
+        }
+        let tmp = self.tmp();
+        let literal = match t {
+            NumberType::Exponent | NumberType::ExponentSign => None,
+            NumberType::BigInt(radix) => Some(LiteralType::BigInt(Cow::Owned(to_decimal(tmp, radix)))),
+            NumberType::Hex | NumberType::Octal | NumberType::Binary | NumberType::LegacyOctal => {
+                let radix = radix_of(t);
+                match i64::from_str_radix(tmp, radix) {
+                    Ok(i) if i <= MAX_SAFE_INTEGER => Some(LiteralType::Integer(i)),
+                    _ => f64::from_str(&to_decimal(tmp, radix)).ok().map(LiteralType::Float)
+                }
+            }
+            _ => decimal(tmp)
+        };
+        match literal {
+            Some(literal) => self.number(literal),
+            None => self.invalid_number()
+        }
+    }
+
     pub fn parse_number(&mut self) -> JsResult<bool> {
-        let mut handled: bool;
+        let mut handled = true;
         loop {
             let c = self.current_char();
             let t = match self.mode() {
                 LexerMode::Number(t) => t,
-                _ => {
-                    panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line())
-                }
+                _ => break
             };
             handled = match (c, t) {
                 (Some('x'), NumberType::None) |
@@ -33,49 +123,101 @@ impl LexerState {
                     self.reset_tmp();
                     true
                 }
-                (Some('0' ... '9'), NumberType::None) => {
-                    self.update(LexerMode::Number(NumberType::NoneLiteral));
-                    self.tmp_push(c.unwrap());
+                (Some('b'), NumberType::None) |
+                (Some('B'), NumberType::None) => {
+                    self.update(LexerMode::Number(NumberType::Binary));
+                    self.reset_tmp();
                     true
                 }
-                (Some('0' ... '7'), _) => {
+                (Some('0' ... '7'), NumberType::None) |
+                (Some('0' ... '7'), NumberType::LegacyOctal) => {
+                    try!(self.mark_legacy_octal("legacy octal literal"));
+                    self.update(LexerMode::Number(NumberType::LegacyOctal));
                     self.tmp_push(c.unwrap());
                     true
                 }
-                (Some('8' ... '9'), NumberType::Octal) => {
-                    panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
+                (Some('8' ... '9'), NumberType::None) |
+                (Some('8' ... '9'), NumberType::LegacyOctal) |
+                (Some('0' ... '9'), NumberType::LegacyDecimal) => {
+                    try!(self.mark_legacy_octal("legacy octal literal"));
+                    self.update(LexerMode::Number(NumberType::LegacyDecimal));
+                    self.tmp_push(c.unwrap());
+                    true
                 }
-                (Some('8' ... '9'), _) => {
+                (Some('0' ... '9'), NumberType::NoneLiteral) |
+                (Some('0' ... '9'), NumberType::Float) => {
                     self.tmp_push(c.unwrap());
                     true
                 }
-                (Some('a' ... 'f'), NumberType::Hex) | (Some('A' ... 'F'), NumberType::Hex) => {
+                (Some('0' ... '9'), NumberType::Exponent) |
+                (Some('0' ... '9'), NumberType::ExponentSign) |
+                (Some('0' ... '9'), NumberType::ExponentDigits) => {
+                    self.update(LexerMode::Number(NumberType::ExponentDigits));
                     self.tmp_push(c.unwrap());
                     true
                 }
-                (Some('.'), NumberType::None) | (Some('.'), NumberType::NoneLiteral) => {
+                (Some(c), NumberType::Hex) |
+                (Some(c), NumberType::Octal) |
+                (Some(c), NumberType::Binary) if c.is_digit(radix_of(t)) => {
+                    self.tmp_push(c);
+                    true
+                }
+                (Some('_'), NumberType::NoneLiteral) |
+                (Some('_'), NumberType::Float) |
+                (Some('_'), NumberType::ExponentDigits) |
+                (Some('_'), NumberType::Hex) |
+                (Some('_'), NumberType::Octal) |
+                (Some('_'), NumberType::Binary) => {
+                    let radix = radix_of(t);
+                    if !self.last_char().map_or(false, |last| last.is_digit(radix)) {
+                        return self.invalid_number()
+                    }
+                    true
+                }
+                (Some('.'), NumberType::None) |
+                (Some('.'), NumberType::NoneLiteral) |
+                (Some('.'), NumberType::LegacyDecimal) => {
+                    if self.after_separator() {
+                        return self.invalid_number()
+                    }
                     self.update(LexerMode::Number(NumberType::Float));
-                    self.tmp_push(c.unwrap());
+                    self.tmp_push('.');
                     true
                 }
-                (_, NumberType::None) | (_, NumberType::NoneLiteral) => {
-                    let i = i64::from_str_radix(&self.tmp(), 10).unwrap();
-                    try!(self.number(LiteralType::Integer(i)));
-                    false
+                (Some('e'), NumberType::None) |
+                (Some('E'), NumberType::None) |
+                (Some('e'), NumberType::NoneLiteral) |
+                (Some('E'), NumberType::NoneLiteral) |
+                (Some('e'), NumberType::LegacyDecimal) |
+                (Some('E'), NumberType::LegacyDecimal) |
+                (Some('e'), NumberType::Float) |
+                (Some('E'), NumberType::Float) => {
+                    if self.after_separator() {
+                        return self.invalid_number()
+                    }
+                    self.update(LexerMode::Number(NumberType::Exponent));
+                    self.tmp_push('e');
+                    true
                 }
-                (_, NumberType::Hex) => {
-                    let i = i64::from_str_radix(&self.tmp(), 16).unwrap();
-                    try!(self.number(LiteralType::Integer(i)));
-                    false
+                (Some('+'), NumberType::Exponent) |
+                (Some('-'), NumberType::Exponent) => {
+                    self.update(LexerMode::Number(NumberType::ExponentSign));
+                    self.tmp_push(c.unwrap());
+                    true
                 }
-                (_, NumberType::Octal) => {
-                    let i = i64::from_str_radix(&self.tmp(), 8).unwrap();
-                    try!(self.number(LiteralType::Integer(i)));
-                    false
+                (Some('n'), NumberType::None) |
+                (Some('n'), NumberType::NoneLiteral) |
+                (Some('n'), NumberType::Hex) |
+                (Some('n'), NumberType::Octal) |
+                (Some('n'), NumberType::Binary) => {
+                    if self.after_separator() {
+                        return self.invalid_number()
+                    }
+                    self.update(LexerMode::Number(NumberType::BigInt(radix_of(t))));
+                    true
                 }
-                (_, NumberType::Float) => {
-                    let i = f64::from_str(&self.tmp()).unwrap();
-                    try!(self.number(LiteralType::Float(i)));
+                (_, _) => {
+                    try!(self.finish_number(t));
                     false
                 }
             };
@@ -88,4 +230,4 @@ impl LexerState {
         }
         Ok(handled)
     }
-}
\ No newline at end of file
+}
@@ -1,18 +1,23 @@
 use error::JsResult;
-use lexer::enums::{LexerMode, TokenType, CommentType, RegexState};
+use lexer::enums::{LexerMode, TokenType, CommentType, RegexState, NumberType};
 use lexer::state::{LexerState};
 
-impl LexerState {
-    fn punctuator(&mut self, t: TokenType) -> JsResult<()> {
+impl<'a> LexerState<'a> {
+    fn punctuator(&mut self, t: TokenType<'a>) -> JsResult<()> {
         self.update(LexerMode::None);
         self.push(t)
     }
 
-    fn mode_punctuator(&mut self, t: TokenType, i: i32) {
+    fn punctuator_before(&mut self, t: TokenType<'a>) -> JsResult<()> {
+        self.update(LexerMode::None);
+        self.push_before(t)
+    }
+
+    fn mode_punctuator(&mut self, t: TokenType<'static>, i: i32) {
         self.update(LexerMode::Punctuator(t, i));
     }
 
-    pub fn parse_punctuator(&mut self, c: Option<char>, t: TokenType, i: i32) -> JsResult<bool> {
+    pub fn parse_punctuator(&mut self, c: Option<char>, t: TokenType<'static>, i: i32) -> JsResult<bool> {
         let handled = match (c, t.clone()) {
             (Some('<'), TokenType::SmallThan) => {
                 self.mode_punctuator(TokenType::LeftShift, 0);
@@ -42,11 +47,22 @@ impl LexerState {
                 }
                 true
             }
+            (Some('0' ... '9'), TokenType::Point) if i == 0 => {
+                self.update(LexerMode::Number(NumberType::Float));
+                self.reset_tmp();
+                let offset = self.current_position().offset - 1;
+                self.tmp_push_at('.', offset);
+                false
+            }
             (_, TokenType::Point) => {
                 if i == 1 {
-                    try!(self.punctuator(TokenType::Point));
+                    let mut end = self.current_position();
+                    end.offset -= 1;
+                    end.col -= 1;
+                    try!(self.push_token(TokenType::Point, end));
+                    self.set_token_start(end);
                 }
-                try!(self.punctuator(TokenType::Point));
+                try!(self.punctuator_before(TokenType::Point));
                 false
             }
             (Some('='), TokenType::RightShiftUnsigned) => {
@@ -85,7 +101,7 @@ impl LexerState {
                 try!(self.punctuator(TokenType::IsNotSame));
                 true
             }
-            (Some('='), TokenType::Divide) => {
+            (Some('='), TokenType::Divide) if !self.regex_allowed() => {
                 try!(self.punctuator(TokenType::DivideAssign));
                 true
             }
@@ -122,7 +138,11 @@ impl LexerState {
                 true
             }
             (Some('&'), TokenType::AndBitwise) => {
-                try!(self.punctuator(TokenType::And));
+                self.mode_punctuator(TokenType::And, 0);
+                true
+            }
+            (Some('='), TokenType::And) => {
+                try!(self.punctuator(TokenType::AndAssign));
                 true
             }
             (Some('*'), TokenType::Multiple) => {
@@ -130,15 +150,40 @@ impl LexerState {
                 true
             }
             (Some('|'), TokenType::OrBitwise) => {
-                try!(self.punctuator(TokenType::Or));
+                self.mode_punctuator(TokenType::Or, 0);
+                true
+            }
+            (Some('='), TokenType::Or) => {
+                try!(self.punctuator(TokenType::OrAssign));
+                true
+            }
+            (Some('?'), TokenType::QuestionMark) => {
+                self.mode_punctuator(TokenType::Nullish, 0);
+                true
+            }
+            (Some('='), TokenType::Nullish) => {
+                try!(self.punctuator(TokenType::NullishAssign));
                 true
             }
+            // `a?.5:b` is a conditional with the number `.5`
+            (Some('.'), TokenType::QuestionMark) => {
+                match self.peek(0) {
+                    Some('0' ... '9') => {
+                        try!(self.punctuator_before(t));
+                        false
+                    }
+                    _ => {
+                        try!(self.punctuator(TokenType::OptionalChaining));
+                        true
+                    }
+                }
+            }
             (Some('-'), TokenType::Minus) => {
                 try!(self.punctuator(TokenType::Decrement));
                 true
             }
             (_, TokenType::SmallThan) | (_, TokenType::GreaterThan) => {
-                try!(self.punctuator(t));
+                try!(self.punctuator_before(t));
                 false
             }
             (Some('/'), TokenType::Divide) => {
@@ -151,26 +196,17 @@ impl LexerState {
                 self.reset_tmp();
                 true
             }
-            (Some(c), TokenType::Divide) => {
-                let last_token = self.last_token();
-                match last_token {
-                    Some(TokenType::Colon) |
-                    Some(TokenType::Equal) |
-                    Some(TokenType::LeftParen) |
-                    Some(TokenType::Comma) => {
-                        self.update(LexerMode::Regex(RegexState::Normal));
-                        self.reset_tmp();
-                        self.tmp_push(c);
-                        true
-                    }
-                    _ => {
-                        try!(self.punctuator(t));
-                        false
-                    }
+            (Some(_), TokenType::Divide) => {
+                if self.regex_allowed() {
+                    self.update(LexerMode::Regex(RegexState::Normal));
+                    self.reset_tmp();
+                } else {
+                    try!(self.punctuator_before(t));
                 }
+                false
             }
             (_, _) => {
-                try!(self.punctuator(t));
+                try!(self.punctuator_before(t));
                 false
             }
         };
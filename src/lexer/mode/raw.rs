@@ -1,58 +1,39 @@
 use error::JsResult;
-use lexer::enums::{LexerMode, TokenType, LiteralType};
+use error::error::{ErrorType, SyntaxErrorType};
+use lexer::enums::{LexerMode, TokenType, Contextual};
 use lexer::state::{LexerState};
+use lexer::unicode::{is_identifier_start, is_identifier_part};
 
-impl LexerState {
+impl<'a> LexerState<'a> {
     fn raw(&mut self) -> JsResult<()> {
+        let escaped = self.mode() == LexerMode::Raw(true);
         self.update(LexerMode::None);
-        let tmp = self.tmp();
-        let tmp = tmp.as_str();
-        let token = match tmp {
-            "var" => TokenType::Var,
-            "if" => TokenType::If,
-            "else" => TokenType::Else,
-            "do" => TokenType::Do,
-            "typeof" => TokenType::Typeof,
-            "switch" => TokenType::Switch,
-            "catch" => TokenType::Catch,
-            "try" => TokenType::Try,
-            "instanceof" => TokenType::Instanceof,
-            "export" => TokenType::Export,
-            "return" => TokenType::Return,
-            "void" => TokenType::Void,
-            "extends" => TokenType::Extends,
-            "const" => TokenType::Const,
-            "finally" => TokenType::Finally,
-            "super" => TokenType::Super,
-            "with" => TokenType::With,
-            "yield" => TokenType::Yield,
-            "default" => TokenType::Default,
-            "function" => TokenType::Function,
-            "of" => TokenType::Of,
-            "in" => TokenType::In,
-            "for" => TokenType::For,
-            "while" => TokenType::While,
-            "class" => TokenType::Class,
-            "break" => TokenType::Break,
-            "continue" => TokenType::Continue,
-            "new" => TokenType::New,
-            "case" => TokenType::Case,
-            "debugger" => TokenType::Debugger,
-            "throw" => TokenType::Throw,
-            "let" => TokenType::Let,
-            "this" => TokenType::This,
-            "target" => TokenType::Target,
-            "delete" => TokenType::Delete,
-            "set" => TokenType::Set,
-            "get" => TokenType::Get,
-            "true" => TokenType::Literal(LiteralType::Boolean(true)),
-            "false" => TokenType::Literal(LiteralType::Boolean(false)),
-            "null" => TokenType::Literal(LiteralType::Null),
-            tmp => {
-                TokenType::Identifier(String::from(tmp))
+        if self.tmp().starts_with('#') {
+            let name = self.tmp_slice(1, self.tmp().len());
+            return self.push_before(TokenType::PrivateName(name))
+        }
+        if escaped {
+            // an escaped keyword is never a keyword
+            let name = self.tmp_text();
+            return self.push_before(TokenType::Identifier(name))
+        }
+        let token = match TokenType::keyword(self.tmp()) {
+            Some(token) => token,
+            None => match Contextual::from_name(self.tmp()) {
+                Some(c) => TokenType::Contextual(c),
+                None => TokenType::Identifier(self.tmp_text())
             }
         };
-        self.push(token)
+        self.push_before(token)
+    }
+
+    /// Whether a `#` starts a private name.
+    pub fn at_private_name(&mut self) -> bool {
+        match self.peek(0) {
+            Some('\\') => true,
+            Some(c) => is_identifier_start(c),
+            None => false
+        }
     }
 
     pub fn parse_raw(&mut self) -> JsResult<bool> {
@@ -60,62 +41,22 @@ impl LexerState {
         loop {
             let c = self.current_char();
             match c {
-                Some('a' ... 'z') | Some('A' ... 'Z') | Some('_') | Some('$') | Some('0' ... '9') => {
-                    self.tmp_push(c.unwrap());
+                Some(c) if is_identifier_part(c) => {
+                    self.tmp_push(c);
                     handled = true
                 }
-                Some(' ') |
-                Some('\t') |
-                Some('\u{c}') |
-                Some('\u{b}') |
-                Some('\u{a0}') |
-                None => {
-                    try!(self.raw());
-                    handled = false
-                }
-                Some('\r') |
-                Some('\n') |
-                Some(':') |
-                Some('*') |
-                Some('+') |
-                Some('-') |
-                Some('!') |
-                Some('{') |
-                Some('}') |
-                Some('(') |
-                Some(')') |
-                Some('[') |
-                Some(']') |
-                Some(';') |
-                Some('.') |
-                Some(',') |
-                Some('<') |
-                Some('>') |
-                Some('?') |
-                Some('%') |
-                Some('=') |
-                Some('&') |
-                Some('|') |
-                Some('/') => {
-                    try!(self.raw());
-                    handled = false
-                }
                 Some('\\') => {
-                    let unicode = self.read_unicode();
-                    match unicode {
-                        Some(c) => {
-                            self.overwrite_current_char_with_unicode(c);
-                            handled = false
-                        }
-                        _ => {
-                            panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
-                        }
+                    let c = try!(self.read_unicode());
+                    if !is_identifier_part(c) {
+                        return Err(self.token_error(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)))
                     }
+                    self.overwrite_current_char_with_unicode(c);
+                    self.update(LexerMode::Raw(true));
+                    handled = false
                 }
                 _ => {
-                    panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
-                    //self.update(LexerMode::EOF);
-                    //true
+                    try!(self.raw());
+                    handled = false
                 }
             }
             if self.mode() == LexerMode::None {
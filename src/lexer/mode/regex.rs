@@ -1,76 +1,98 @@
 use error::JsResult;
-use lexer::enums::{LexerMode, RegexState, TokenType, LiteralType, RegexIdentifier};
+use error::error::{ErrorType, SyntaxErrorType};
+use lexer::enums::{LexerMode, RegexState, TokenType, LiteralType};
 use lexer::state::{LexerState};
 
-impl LexerState {
-    fn regex(&mut self, t: RegexIdentifier) -> JsResult<()> {
-        let tmp = self.tmp();
-        try!(self.push(TokenType::Literal(LiteralType::Regex(tmp, t))));
+impl<'a> LexerState<'a> {
+    /// Splits the scanned `body/flags` text and pushes the regex ending before the current char.
+    fn regex(&mut self) -> JsResult<()> {
+        let len = self.tmp().len();
+        let split = self.tmp().rfind('/').unwrap_or(len);
+        let pattern = self.tmp_slice(0, split);
+        let flags = self.tmp_slice(split + 1, len);
+        if flags.contains('u') && flags.contains('v') {
+            return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::InvalidRegexFlag('v'))))
+        }
+        try!(self.push_before(TokenType::Literal(LiteralType::Regex(pattern, flags))));
         self.update(LexerMode::None);
         Ok(())
     }
 
+    fn regex_flag(&mut self, c: char) -> JsResult<()> {
+        let repeated = {
+            let tmp = self.tmp();
+            tmp[tmp.rfind('/').unwrap_or(0)..].contains(c)
+        };
+        match c {
+            'd' | 'g' | 'i' | 'm' | 's' | 'u' | 'v' | 'y' if !repeated => {
+                self.tmp_push(c);
+                Ok(())
+            }
+            _ => Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::InvalidRegexFlag(c))))
+        }
+    }
+
     pub fn parse_regex(&mut self) -> JsResult<bool> {
-        let mut handled: bool;
+        let mut handled = true;
         loop {
             let c = self.current_char();
             let t = match self.mode() {
                 LexerMode::Regex(t) => t,
-                _ => {
-                    panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line())
-                }
+                _ => break
             };
             let escaped = self.is_escaped();
             handled = match (c, t, escaped) {
-                (Some('/'), RegexState::Normal, false) => {
-                    self.update(LexerMode::Regex(RegexState::Identifier));
-                    true
+                (None, RegexState::Flags, _) => {
+                    try!(self.regex());
+                    false
                 }
-                (Some('/'), RegexState::Normal, true) => {
-                    self.tmp_push('/');
-                    self.escaped(false);
-                    true
+                (Some(c), RegexState::Flags, _) => {
+                    match c {
+                        'a' ... 'z' | 'A' ... 'Z' | '0' ... '9' | '_' | '$' => {
+                            try!(self.regex_flag(c));
+                            true
+                        }
+                        _ => {
+                            try!(self.regex());
+                            false
+                        }
+                    }
                 }
-                (Some('g'), RegexState::Identifier, _) => {
-                    try!(self.regex(RegexIdentifier::Global));
-                    true
+                (None, _, _) |
+                (Some('\n'), _, _) |
+                (Some('\r'), _, _) |
+                (Some('\u{2028}'), _, _) |
+                (Some('\u{2029}'), _, _) => {
+                    return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedRegex)))
                 }
-                (Some('i'), RegexState::Identifier, _) => {
-                    try!(self.regex(RegexIdentifier::Ignore));
+                (Some(c), _, true) => {
+                    self.escaped(false);
+                    self.tmp_push(c);
                     true
                 }
-                (Some('\\'), RegexState::Normal, false) => {
+                (Some('\\'), _, false) => {
                     self.escaped(true);
-                    true
-                }
-                (Some('\\'), RegexState::Normal, true) => {
                     self.tmp_push('\\');
-                    self.escaped(false);
                     true
                 }
-                (Some(_), RegexState::Identifier, _) => {
-                    try!(self.regex(RegexIdentifier::None));
-                    false
-                }
-                (Some(c), RegexState::Normal, true) => {
-                    self.escaped(false);
-                    self.tmp_push('\\');
-                    self.tmp_push(c);
+                (Some('/'), RegexState::Normal, false) => {
+                    self.update(LexerMode::Regex(RegexState::Flags));
+                    self.tmp_push('/');
                     true
                 }
-                (Some(c), RegexState::Normal, false) => {
-                    self.tmp_push(c);
+                (Some('['), RegexState::Normal, false) => {
+                    self.update(LexerMode::Regex(RegexState::Class));
+                    self.tmp_push('[');
                     true
                 }
-                (None, RegexState::Identifier, _) => {
-                    try!(self.regex(RegexIdentifier::None));
-                    self.update(LexerMode::EOF);
+                (Some(']'), RegexState::Class, false) => {
+                    self.update(LexerMode::Regex(RegexState::Normal));
+                    self.tmp_push(']');
                     true
                 }
-                (None, RegexState::Normal, _) => {
-                    panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
-                    //self.update(LexerMode::EOF);
-                    //true
+                (Some(c), _, false) => {
+                    self.tmp_push(c);
+                    true
                 }
             };
             if self.mode() == LexerMode::None {
@@ -81,6 +103,5 @@ impl LexerState {
             }
         }
         Ok(handled)
-
     }
 }
@@ -3,51 +3,194 @@ use error::error::{ErrorType, SyntaxErrorType};
 use lexer::enums::{LexerMode, TokenType, LiteralType};
 use lexer::enums::StringType::*;
 use lexer::state::{LexerState};
+use lexer::token::Span;
+use std::borrow::Cow;
+use std::char;
+use std::iter::Peekable;
+use std::str::Chars;
+
+macro_rules! try_opt {
+    ($expr:expr) => (match $expr {
+        Some(item) => item,
+        None => return None,
+    })
+}
+
+fn hex_digits(chars: &mut Peekable<Chars>, count: usize) -> Option<u32> {
+    let mut value = 0;
+    for _ in 0..count {
+        value = value * 16 + try_opt!(chars.next().and_then(|c| c.to_digit(16)));
+    }
+    Some(value)
+}
+
+/// Reads the code point of a `\u` escape, after the `u`.
+fn unicode_escape(chars: &mut Peekable<Chars>) -> Option<u32> {
+    if chars.peek() != Some(&'{') {
+        return hex_digits(chars, 4)
+    }
+    chars.next();
+    let mut value: u32 = 0;
+    let mut digits = 0;
+    loop {
+        match chars.next() {
+            Some('}') if digits > 0 => return Some(value),
+            Some(c) => {
+                value = value * 16 + try_opt!(c.to_digit(16));
+                digits += 1;
+                if value > 0x10FFFF {
+                    return None
+                }
+            }
+            None => return None
+        }
+    }
+}
+
+/// Whether `raw` has a legacy octal escape like `\017`, or `\8` or `\9`, which strict code does
+/// not allow. `\0` is fine as long as no digit follows it.
+pub fn has_legacy_octal_escape(raw: &str) -> bool {
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            continue
+        }
+        match chars.next() {
+            Some('0') if chars.peek().map_or(false, |c| c.is_digit(10)) => return true,
+            Some('1' ... '9') => return true,
+            _ => ()
+        }
+    }
+    false
+}
+
+/// Decodes the escape sequences in the text of a string or template literal. Returns `None` for
+/// a malformed escape, and in templates also for the legacy octal escapes they do not allow.
+pub fn cook(raw: &str, template: bool) -> Option<String> {
+    let mut cooked = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    // a `\uD83D` high surrogate waiting for the low half of its pair
+    let mut surrogate: Option<u32> = None;
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            if surrogate.take().is_some() {
+                cooked.push('\u{fffd}');
+            }
+            cooked.push(c);
+            continue
+        }
+        let escape = try_opt!(chars.next());
+        let code = match escape {
+            'u' => try_opt!(unicode_escape(&mut chars)),
+            'x' => try_opt!(hex_digits(&mut chars, 2)),
+            '0' ... '7' => {
+                let legacy = escape != '0' || chars.peek().map_or(false, |c| c.is_digit(10));
+                if legacy && template {
+                    return None
+                }
+                let mut value = escape.to_digit(8).unwrap_or(0);
+                // up to three digits, as long as the value stays below 0o400
+                let max = if escape <= '3' { 2 } else { 1 };
+                for _ in 0..max {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            value = value * 8 + digit;
+                            chars.next();
+                        }
+                        None => break
+                    }
+                }
+                value
+            }
+            '8' | '9' if template => return None,
+            'n' => '\n' as u32,
+            't' => '\t' as u32,
+            'r' => '\r' as u32,
+            'b' => 0x8,
+            'f' => 0xc,
+            'v' => 0xb,
+            '\r' => {
+                if chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                continue
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => continue,
+            c => c as u32
+        };
+        match (surrogate.take(), code) {
+            (Some(high), 0xDC00 ... 0xDFFF) => {
+                let pair = 0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00);
+                cooked.push(char::from_u32(pair).unwrap_or('\u{fffd}'));
+                continue
+            }
+            (Some(_), _) => cooked.push('\u{fffd}'),
+            (None, _) => ()
+        }
+        match code {
+            0xD800 ... 0xDBFF => surrogate = Some(code),
+            _ => cooked.push(char::from_u32(code).unwrap_or('\u{fffd}'))
+        }
+    }
+    if surrogate.is_some() {
+        cooked.push('\u{fffd}');
+    }
+    Some(cooked)
+}
+
+impl<'a> LexerState<'a> {
+    fn string(&mut self, quote: char) -> JsResult<()> {
+        let value = if self.tmp().contains('\\') {
+            if has_legacy_octal_escape(self.tmp()) {
+                try!(self.mark_legacy_octal("octal escape sequence"));
+            }
+            match cook(self.tmp(), false) {
+                Some(value) => Cow::Owned(value),
+                None => return Err(self.token_error(ErrorType::SyntaxError(SyntaxErrorType::InvalidEscape)))
+            }
+        } else {
+            self.tmp_text()
+        };
+        let span = Span::new(self.token_start(), self.next_position());
+        let raw = match self.source_text(span) {
+            Some(raw) => Cow::Borrowed(raw),
+            None => Cow::Owned(format!("{}{}{}", quote, self.tmp(), quote))
+        };
+        try!(self.push(TokenType::Literal(LiteralType::String(value, raw))));
+        self.update(LexerMode::None);
+        Ok(())
+    }
 
-impl LexerState {
     pub fn parse_string(&mut self) -> JsResult<bool> {
         loop {
             let escaped = self.is_escaped();
             let c = self.current_char();
             let t = match self.mode() {
                 LexerMode::String(t) => t,
-                _ => {
-                    panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line())
-                }
+                _ => break
             };
             match (c, escaped, t) {
-                (Some('"'), true, DoubleQuote) => {
-                    self.escaped(false);
-                    self.tmp_push('"');
-                }
-                (Some('"'), false, DoubleQuote) => {
-                    let tmp = self.tmp();
-                    try!(self.push(TokenType::Literal(LiteralType::String(tmp))));
-                    self.update(LexerMode::None);
-                }
-                (Some('\''), true, SingleQuote) => {
-                    self.escaped(false);
-                    self.tmp_push('\'');
-                }
-                (Some('\''), false, SingleQuote) => {
-                    let tmp = self.tmp();
-                    try!(self.push(TokenType::Literal(LiteralType::String(tmp))));
-                    self.update(LexerMode::None);
-                }
+                (Some('"'), false, DoubleQuote) => try!(self.string('"')),
+                (Some('\''), false, SingleQuote) => try!(self.string('\'')),
                 (Some('\\'), false, _) => {
                     self.escaped(true);
-                }
-                (Some('\\'), true, _) => {
-                    self.escaped(false);
                     self.tmp_push('\\');
                 }
+                (Some('\n'), false, _) if self.tmp().ends_with("\\\r") => {
+                    // second half of an escaped `\r\n` line continuation
+                    self.tmp_push('\n');
+                }
+                (Some('\n'), false, _) |
+                (Some('\r'), false, _) => {
+                    let err = self.error(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedString));
+                    return Err(err);
+                }
                 (Some(c), true, _) => {
                     self.escaped(false);
-                    self.tmp_push('\\');
                     self.tmp_push(c);
                 }
-                (Some(x), _, _) => {
-                    self.tmp_push(x)
+                (Some(c), false, _) => {
+                    self.tmp_push(c)
                 }
                 (None, _, _) => {
                     let err = self.error(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF));
@@ -61,4 +204,4 @@ impl LexerState {
         }
         Ok(true)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,97 @@
+use error::JsResult;
+use error::error::{ErrorType, SyntaxErrorType};
+use lexer::enums::{LexerMode, TokenType, TemplateType, TemplateString};
+use lexer::mode::string::cook;
+use lexer::state::{LexerState};
+use std::borrow::Cow;
+
+impl<'a> LexerState<'a> {
+    fn template(&mut self, t: TemplateType, tail: bool) -> JsResult<()> {
+        let cooked = if self.tmp().contains('\\') {
+            cook(self.tmp(), true).map(Cow::Owned)
+        } else {
+            Some(self.tmp_text())
+        };
+        let s = TemplateString {
+            cooked: cooked,
+            raw: self.tmp_text()
+        };
+        let token = match (t, tail) {
+            (TemplateType::Head, true) => TokenType::NoSubstitutionTemplate(s),
+            (TemplateType::Head, false) => TokenType::TemplateHead(s),
+            (TemplateType::Middle, true) => TokenType::TemplateTail(s),
+            (TemplateType::Middle, false) => TokenType::TemplateMiddle(s)
+        };
+        try!(self.push(token));
+        self.update(LexerMode::None);
+        Ok(())
+    }
+
+    pub fn start_template(&mut self, t: TemplateType) {
+        self.update(LexerMode::Template(t, false));
+        self.reset_tmp();
+    }
+
+    pub fn parse_template(&mut self) -> JsResult<bool> {
+        let mut handled = true;
+        loop {
+            let escaped = self.is_escaped();
+            let c = self.current_char();
+            let (t, dollar) = match self.mode() {
+                LexerMode::Template(t, dollar) => (t, dollar),
+                _ => break
+            };
+            handled = match (c, escaped, dollar) {
+                (Some('{'), _, true) => {
+                    try!(self.template(t, false));
+                    self.open_substitution();
+                    true
+                }
+                (_, _, true) => {
+                    self.tmp_push('$');
+                    self.update(LexerMode::Template(t, false));
+                    false
+                }
+                (Some('\n'), _, _) if self.last_char() == Some('\r') => true,
+                (Some('\r'), _, _) => {
+                    // line breaks are normalized to `\n` in both the raw and cooked value
+                    self.escaped(false);
+                    self.tmp_push('\n');
+                    true
+                }
+                (Some('`'), false, _) => {
+                    try!(self.template(t, true));
+                    true
+                }
+                (Some('$'), false, _) => {
+                    self.update(LexerMode::Template(t, true));
+                    true
+                }
+                (Some('\\'), false, _) => {
+                    self.escaped(true);
+                    self.tmp_push('\\');
+                    true
+                }
+                (Some(c), true, _) => {
+                    self.escaped(false);
+                    self.tmp_push(c);
+                    true
+                }
+                (Some(c), false, _) => {
+                    self.tmp_push(c);
+                    true
+                }
+                (None, _, _) => {
+                    return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF)))
+                }
+            };
+            if self.mode() == LexerMode::None {
+                break
+            }
+            if handled {
+                self.next_char();
+            }
+        }
+        Ok(handled)
+    }
+}
@@ -1,91 +1,246 @@
-use lexer::enums::{TokenType, LexerMode};
-use lexer::token::Token;
+use lexer::enums::{TokenType, LexerMode, Goal, RegexState, SourceType, Contextual};
+use lexer::token::{Token, Position, Span, Comment, CommentKind};
+use lexer::unicode::is_line_terminator;
 use error::JsResult;
 use error::error::{Error, ErrorType, SyntaxErrorType};
+use std::borrow::Cow;
 use std::char;
+use std::collections::VecDeque;
+use std::mem;
 
-pub type LexerStateIterator = Box<Iterator<Item = char>>;
+pub type LexerStateIterator<'a> = Box<Iterator<Item = char> + 'a>;
 
-pub struct LexerState {
-    input: LexerStateIterator,
-    tokens: Vec<Token>,
-    last_token: Option<TokenType>,
+enum Input<'a> {
+    /// The whole input as a `&str`, chars are read from it in place and token text is borrowed
+    /// from it where possible.
+    Source(&'a str),
+    /// Chars streamed from an iterator, token text is always owned.
+    Chars(LexerStateIterator<'a>)
+}
+
+pub struct LexerState<'a> {
+    input: Input<'a>,
+    /// Chars read ahead of `current_char` by `peek` from streamed input.
+    lookahead: VecDeque<char>,
+    tokens: VecDeque<Token<'a>>,
+    comments: Vec<Comment<'a>>,
+    last_token: Option<TokenType<'a>>,
     mode: LexerMode,
+    /// Text of the token being scanned once it differs from the source, see `tmp_push`.
     tmp: String,
+    /// Set when the text is in `tmp`, until then it is `tmp_start..tmp_end` of the source.
+    tmp_owned: bool,
+    tmp_start: usize,
+    tmp_end: usize,
     escaped: bool,
     last_char: Option<char>,
     current_char: Option<char>,
     last_char_is_unicode: bool,
-    col: u32,
-    line: u64
+    /// Position of `current_char`.
+    position: Position,
+    /// Position just past `current_char`.
+    next: Position,
+    /// Position where the token being scanned starts.
+    start: Position,
+    /// Open braces in each template substitution being scanned, innermost last.
+    templates: Vec<u32>,
+    /// Set when `current_char` still has to be dispatched, after a mode gave it back unhandled.
+    pending: bool,
+    /// Goal requested by the parser, `None` when the whole input is lexed up front.
+    goal: Option<Goal>,
+    source_type: SourceType,
+    /// Set when a line terminator was read since the last token, it is recorded on the next one.
+    newline: bool,
+    /// Set when the token being scanned is a legacy octal literal or has a legacy octal escape.
+    legacy_octal: bool
 }
 
-impl LexerState {
-    pub fn new(input: LexerStateIterator) -> LexerState {
+impl<'a> LexerState<'a> {
+    pub fn new(input: LexerStateIterator<'a>) -> LexerState<'a> {
+        LexerState::with_input(Input::Chars(input))
+    }
+
+    /// Lexes `source` in place, identifiers and literals without escapes borrow their text from it.
+    pub fn from_source(source: &'a str) -> LexerState<'a> {
+        LexerState::with_input(Input::Source(source))
+    }
+
+    fn with_input(input: Input<'a>) -> LexerState<'a> {
+        let owned = match input {
+            Input::Source(_) => false,
+            Input::Chars(_) => true
+        };
         LexerState {
             input: input,
-            tokens: Vec::new(),
+            lookahead: VecDeque::new(),
+            tokens: VecDeque::new(),
+            comments: Vec::new(),
             mode: LexerMode::None,
             tmp: String::new(),
+            tmp_owned: owned,
+            tmp_start: 0,
+            tmp_end: 0,
             escaped: false,
             last_char: None,
             last_char_is_unicode: false,
             last_token: None,
             current_char: None,
-            col: 1,
-            line: 1
+            position: Position::new(0, 1, 1),
+            next: Position::new(0, 1, 1),
+            start: Position::new(0, 1, 1),
+            templates: Vec::new(),
+            pending: false,
+            goal: None,
+            source_type: SourceType::Script,
+            newline: false,
+            legacy_octal: false
+        }
+    }
+
+    /// The source text covered by `span`, `None` when the input is not a `&str`.
+    pub fn source_text(&self, span: Span) -> Option<&'a str> {
+        match self.input {
+            Input::Source(source) => source.get(span.start.offset..span.end.offset),
+            Input::Chars(_) => None
         }
     }
 
+    pub fn source_type(&self) -> SourceType {
+        self.source_type
+    }
+
+    pub fn set_source_type(&mut self, t: SourceType) {
+        self.source_type = t
+    }
+
     pub fn parse(&mut self) -> JsResult<()> {
-        loop {
+        while !self.finished() {
+            try!(self.step());
+        }
+        Ok(())
+    }
+
+    /// Scans until the next token is complete, `goal` decides how a `/` is read.
+    pub fn next_token(&mut self, goal: Goal) -> JsResult<Option<Token<'a>>> {
+        self.goal = Some(goal);
+        self.pull()
+    }
+
+    fn pull(&mut self) -> JsResult<Option<Token<'a>>> {
+        while self.tokens.is_empty() {
+            if self.finished() {
+                return Ok(None)
+            }
+            try!(self.step());
+        }
+        Ok(self.tokens.pop_front())
+    }
+
+    /// Scans a `/` or `/=` token that `next_token` just returned again, as the start of a regex.
+    pub fn rescan_regex(&mut self, slash: Token<'a>) -> JsResult<Token<'a>> {
+        self.reset_tmp();
+        if slash.token == TokenType::DivideAssign {
+            // the `=` is the first char of the pattern
+            self.tmp_push_at('=', slash.span.end.offset - 1);
+        }
+        self.set_token_start(slash.span.start);
+        self.update(LexerMode::Regex(RegexState::Normal));
+        match try!(self.next_token(Goal::RegExp)) {
+            Some(token) => Ok(token),
+            None => Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedRegex)))
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.mode == LexerMode::EOF && !self.pending
+    }
+
+    fn step(&mut self) -> JsResult<()> {
+        if !self.pending {
             self.next_char();
-            let mut done = false; // mut done: bool
-            while !done {
-                let mode = self.mode();
-                let c = self.current_char();
-                let result = match mode {
-                    LexerMode::None => self.parse_normal(c),
-                    LexerMode::String(_) => self.parse_string(),
-                    LexerMode::Punctuator(t, i) => self.parse_punctuator(c, t, i),
-                    LexerMode::Number(_) => self.parse_number(),
-                    LexerMode::Comment(_) => self.parse_comment(),
-                    LexerMode::Raw => self.parse_raw(),
-                    LexerMode::Regex(_) => self.parse_regex(),
-                    LexerMode::EOF => Ok(true)
-                };
-                done = match result {
-                    Ok(t) => t,
-                    Err(err) => {
-                        return Err(err)
-                    }
+        }
+        let c = self.current_char();
+        let handled = try!(match self.mode() {
+            LexerMode::None => {
+                // an escaped identifier start re-enters here with the decoded char
+                if !self.last_char_is_unicode() {
+                    self.start_token();
                 }
+                self.parse_normal(c)
             }
-            if self.mode() == LexerMode::EOF {
-                break;
+            LexerMode::String(_) => self.parse_string(),
+            LexerMode::Punctuator(t, i) => self.parse_punctuator(c, t, i),
+            LexerMode::Number(_) => self.parse_number(),
+            LexerMode::Comment(_) => self.parse_comment(),
+            LexerMode::Raw(_) => self.parse_raw(),
+            LexerMode::Regex(_) => self.parse_regex(),
+            LexerMode::Template(_, _) => self.parse_template(),
+            LexerMode::EOF => Ok(true)
+        });
+        self.pending = !handled;
+        Ok(())
+    }
+
+    /// Whether a `/` starts a regex. Without a goal from the parser this guesses from the previous
+    /// token, a `/` at the start or after something that ends an expression is a division.
+    pub fn regex_allowed(&self) -> bool {
+        match self.goal {
+            Some(goal) => goal == Goal::RegExp,
+            None => match self.last_token {
+                Some(TokenType::Contextual(Contextual::Yield)) |
+                Some(TokenType::Contextual(Contextual::Await)) => true,
+                Some(TokenType::Identifier(_)) |
+                Some(TokenType::PrivateName(_)) |
+                Some(TokenType::Contextual(_)) |
+                Some(TokenType::Literal(_)) |
+                Some(TokenType::NoSubstitutionTemplate(_)) |
+                Some(TokenType::TemplateTail(_)) |
+                Some(TokenType::RightParen) |
+                Some(TokenType::RightBracket) |
+                Some(TokenType::RightBrace) |
+                Some(TokenType::This) |
+                Some(TokenType::Super) |
+                Some(TokenType::Increment) |
+                Some(TokenType::Decrement) |
+                None => false,
+                _ => true
             }
         }
-        Ok(())
     }
 
-    pub fn read_unicode(&mut self) -> Option<char> {
-        let indicated = self.next_char();
-        match indicated {
-            Some('u') => {
-                let mut tmp = String::new();
-                let a1 = self.next_char().unwrap();
-                tmp.push(a1);
-                let a1 = self.next_char().unwrap();
-                tmp.push(a1);
-                let a1 = self.next_char().unwrap();
-                tmp.push(a1);
-                let a1 = self.next_char().unwrap();
-                tmp.push(a1);
-                let i = u32::from_str_radix(&tmp, 16).unwrap();
+    /// Reads the `uXXXX` or `u{X...}` part of a unicode escape sequence after its backslash.
+    pub fn read_unicode(&mut self) -> JsResult<char> {
+        if self.next_char() != Some('u') {
+            return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)))
+        }
+        let mut code = 0;
+        if self.next_char() == Some('{') {
+            let mut digits = 0;
+            loop {
+                match self.next_char() {
+                    Some('}') if digits > 0 => break,
+                    Some(c) if c.is_ascii_hexdigit() && code <= 0x10ffff => {
+                        code = code * 16 + c.to_digit(16).unwrap();
+                        digits += 1
+                    }
+                    _ => return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)))
+                }
+            }
+        } else {
+            for i in 0..4 {
+                let c = if i == 0 { self.current_char() } else { self.next_char() };
+                match c.and_then(|c| c.to_digit(16)) {
+                    Some(digit) => code = code * 16 + digit,
+                    None => return Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)))
+                }
+            }
+        }
+        match char::from_u32(code) {
+            Some(c) => {
                 self.last_char_is_unicode = true;
-                char::from_u32(i)
-            },
-            _ => None
+                Ok(c)
+            }
+            None => Err(self.error(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)))
         }
     }
 
@@ -98,7 +253,22 @@ impl LexerState {
     }
 
     pub fn error(&mut self, t: ErrorType) -> Error {
-        Error::new(t, self.col, self.line, None)
+        Error::new(t, Span::new(self.position, self.next), Vec::new())
+    }
+
+    /// Marks the token being scanned as a legacy octal literal or escape. Modules are strict from
+    /// the start and fail right away, other code only once the parser knows it is strict.
+    pub fn mark_legacy_octal(&mut self, what: &'static str) -> JsResult<()> {
+        if self.source_type == SourceType::Module {
+            return Err(self.token_error(ErrorType::SyntaxError(SyntaxErrorType::StrictMode(what))))
+        }
+        self.legacy_octal = true;
+        Ok(())
+    }
+
+    /// An error covering the token scanned so far, up to and including the current char.
+    pub fn token_error(&mut self, t: ErrorType) -> Error {
+        Error::new(t, Span::new(self.start, self.next), Vec::new())
     }
 
     pub fn overwrite_current_char_with_unicode(&mut self, c: char) {
@@ -108,11 +278,58 @@ impl LexerState {
     }
 
     pub fn col(&mut self) -> u32 {
-        self.col
+        self.position.col
     }
 
     pub fn line(&mut self) -> u64 {
-        self.line
+        self.position.line
+    }
+
+    pub fn current_position(&self) -> Position {
+        self.position
+    }
+
+    pub fn next_position(&self) -> Position {
+        self.next
+    }
+
+    /// Marks the current char as the first char of the next token.
+    pub fn start_token(&mut self) {
+        self.start = self.position
+    }
+
+    pub fn token_start(&self) -> Position {
+        self.start
+    }
+
+    pub fn set_token_start(&mut self, start: Position) {
+        self.start = start
+    }
+
+    pub fn open_substitution(&mut self) {
+        self.templates.push(0)
+    }
+
+    pub fn open_brace(&mut self) {
+        if let Some(depth) = self.templates.last_mut() {
+            *depth += 1
+        }
+    }
+
+    /// Returns true when a `}` closes the innermost template substitution instead of a block.
+    pub fn close_brace(&mut self) -> bool {
+        let closes = match self.templates.last_mut() {
+            Some(depth) if *depth > 0 => {
+                *depth -= 1;
+                false
+            }
+            Some(_) => true,
+            None => false
+        };
+        if closes {
+            self.templates.pop();
+        }
+        closes
     }
 
     pub fn escaped(&mut self, e: bool) {
@@ -123,35 +340,150 @@ impl LexerState {
         self.escaped
     }
 
+    /// Empties the token text, keeping the buffer of `tmp` for the next token.
     pub fn reset_tmp(&mut self) {
-        self.tmp = String::new()
+        self.tmp.clear();
+        self.tmp_owned = match self.input {
+            Input::Source(_) => false,
+            Input::Chars(_) => true
+        };
+        self.tmp_start = 0;
+        self.tmp_end = 0;
     }
 
-    pub fn tmp(&mut self) -> String {
-        self.tmp.clone()
+    /// The text of the token being scanned.
+    pub fn tmp(&self) -> &str {
+        if self.tmp_owned {
+            &self.tmp
+        } else {
+            self.borrowed_tmp()
+        }
     }
 
+    fn borrowed_tmp(&self) -> &'a str {
+        match self.input {
+            Input::Source(source) => &source[self.tmp_start..self.tmp_end],
+            Input::Chars(_) => ""
+        }
+    }
+
+    /// The text of the token being scanned, borrowed from the source unless it differs from it.
+    pub fn tmp_text(&self) -> Cow<'a, str> {
+        let len = self.tmp().len();
+        self.tmp_slice(0, len)
+    }
+
+    /// Like `tmp_text`, for the bytes `start..end` of the text.
+    pub fn tmp_slice(&self, start: usize, end: usize) -> Cow<'a, str> {
+        if self.tmp_owned {
+            Cow::Owned(String::from(&self.tmp[start..end]))
+        } else {
+            Cow::Borrowed(&self.borrowed_tmp()[start..end])
+        }
+    }
+
+    /// Appends `c`, usually the current char, to the token text.
     pub fn tmp_push(&mut self, c: char) {
+        let offset = self.position.offset;
+        self.tmp_push_at(c, offset)
+    }
+
+    /// Appends `c` to the token text, `offset` is where the text starts in the source if it is
+    /// the first char. The text only grows a range of the source while each char is the one
+    /// that follows it there, it is copied to `tmp` at the first one that is not.
+    pub fn tmp_push_at(&mut self, c: char, offset: usize) {
+        if !self.tmp_owned {
+            let source = match self.input {
+                Input::Source(source) => source,
+                Input::Chars(_) => ""
+            };
+            if self.tmp_start == self.tmp_end {
+                self.tmp_start = offset;
+                self.tmp_end = offset;
+            }
+            if source[self.tmp_end..].starts_with(c) {
+                self.tmp_end += c.len_utf8();
+                return
+            }
+            self.tmp.push_str(&source[self.tmp_start..self.tmp_end]);
+            self.tmp_owned = true;
+        }
         self.tmp.push(c)
     }
 
+    /// Removes the last char of the token text.
+    pub fn tmp_pop(&mut self) {
+        if self.tmp_owned {
+            self.tmp.pop();
+        } else if let Some(c) = self.borrowed_tmp().chars().next_back() {
+            self.tmp_end -= c.len_utf8();
+        }
+    }
+
+    /// Reads the char after `current_char`, straight from the source or from streamed input.
+    fn read_char(&mut self) -> Option<char> {
+        match self.input {
+            Input::Source(source) => source[self.next.offset..].chars().next(),
+            Input::Chars(ref mut chars) => match self.lookahead.pop_front() {
+                Some(c) => Some(c),
+                None => chars.next()
+            }
+        }
+    }
+
     pub fn next_char(&mut self) -> Option<char> {
         self.last_char = self.current_char;
-        let char = self.input.next();
-        match char {
-            Some('\n') => {
-                self.line += 1;
-                self.col = 1;
-            }
-            _ => {
-                self.col += 1;
-            }
+        let char = self.read_char();
+        self.position = self.next;
+        match (self.current_char, char) {
+            // `\r\n` is a single line break, and a leading BOM takes no column
+            (Some('\r'), Some('\n')) => self.next.offset += 1,
+            (None, Some('\u{feff}')) if self.next.offset == 0 => self.next.offset += 3,
+            (_, Some(c)) => self.next.advance(c),
+            (_, None) => ()
         }
         self.last_char_is_unicode = false;
         self.current_char = char;
         char
     }
 
+    /// Looks at the char `n` places after `current_char` without consuming it.
+    pub fn peek(&mut self, n: usize) -> Option<char> {
+        let chars = match self.input {
+            Input::Source(source) => return source[self.next.offset..].chars().nth(n),
+            Input::Chars(ref mut chars) => chars
+        };
+        while self.lookahead.len() <= n {
+            match chars.next() {
+                Some(c) => self.lookahead.push_back(c),
+                None => return None
+            }
+        }
+        Some(self.lookahead[n])
+    }
+
+    /// Consumes the next `n` chars, which the caller already looked at with `peek`.
+    pub fn skip_chars(&mut self, n: usize) {
+        for _ in 0..n {
+            self.next_char();
+        }
+    }
+
+    /// Whether `current_char` is the first char of the input.
+    pub fn at_input_start(&self) -> bool {
+        self.position.offset == 0
+    }
+
+    /// Whether only whitespace and comments follow the last line break or the start of input,
+    /// where an Annex B `-->` starts a comment.
+    pub fn at_line_start(&self) -> bool {
+        self.newline || self.last_token.is_none()
+    }
+
+    pub fn line_break(&mut self) {
+        self.newline = true
+    }
+
     pub fn current_char(&self) -> Option<char> {
         self.current_char
     }
@@ -168,134 +500,77 @@ impl LexerState {
         self.mode = t
     }
 
-    pub fn last_token(&self) -> Option<TokenType> {
+    pub fn last_token(&self) -> Option<TokenType<'a>> {
         self.last_token.clone()
     }
 
-    pub fn push(&mut self, t: TokenType) -> JsResult<()>{
-        let t = match t {
-            TokenType::CommentLiteral(_) => None,
-            TokenType::LineTerminate => {
-                match self.last_token {
-                    None => None,
-                    Some(TokenType::Semicolon) => None,
-                    Some(TokenType::Yield) => {
-                        return Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOL), self.col, self.line, None))
-                    },
-                    _ => Some(TokenType::LineTerminate)
-                }
-            }
-            TokenType::Lamda => {
-                match self.last_token {
-                    Some(TokenType::LineTerminate) => {
-                        return Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOL), self.col, self.line, None))
-                    },
-                    _ => Some(TokenType::Lamda)
-                }
-            }
-            t => {
-                Some(t)
-            }
-        };
-        match t {
-            Some(t) => {
-                self.last_token = Some(t.clone());
-                let token = Token::new(t, self.col, self.line);
-                self.tokens.push(token)
-            }
-            None => ()
-        };
+    /// Pushes a token that ends with the current char.
+    pub fn push(&mut self, t: TokenType<'a>) -> JsResult<()> {
+        let end = self.next;
+        self.push_token(t, end)
+    }
+
+    /// Pushes a token that ends before the current char, for tokens only terminated by
+    /// looking at the char that follows them.
+    pub fn push_before(&mut self, t: TokenType<'a>) -> JsResult<()> {
+        let end = self.position;
+        self.push_token(t, end)
+    }
+
+    pub fn push_token(&mut self, t: TokenType<'a>, end: Position) -> JsResult<()> {
+        self.last_token = Some(t.clone());
+        let mut token = Token::new(t, Span::new(self.start, end));
+        token.newline_before = self.newline;
+        token.legacy_octal = self.legacy_octal;
+        self.newline = false;
+        self.legacy_octal = false;
+        self.tokens.push_back(token);
         Ok(())
     }
 
-    pub fn tokens(&self) -> Vec<Token> {
-        self.tokens.clone()
+    pub fn tokens(&self) -> Vec<Token<'a>> {
+        self.tokens.iter().cloned().collect()
+    }
+
+    /// Records the comment in `tmp`. Block comments end with the current char, the others end
+    /// before the line terminator or the end of input.
+    pub fn push_comment(&mut self, kind: CommentKind) {
+        let end = match kind {
+            CommentKind::Block => self.next,
+            _ => self.position
+        };
+        let text = self.tmp_text();
+        if kind == CommentKind::Block && text.chars().any(is_line_terminator) {
+            self.newline = true
+        }
+        self.comments.push(Comment::new(kind, text, Span::new(self.start, end)))
+    }
+
+    /// All comments read so far, in source order.
+    pub fn comments(&self) -> Vec<Comment<'a>> {
+        self.comments.clone()
+    }
+
+    /// Removes the comments read so far, so that streaming a long input does not keep them all.
+    pub fn take_comments(&mut self) -> Vec<Comment<'a>> {
+        mem::replace(&mut self.comments, Vec::new())
     }
 }
 
-impl Iterator for LexerState {
-    type Item = Token;
+/// Pulls tokens on demand, guessing how to read a `/` from the previous token like `parse`.
+/// The stream ends after the first error.
+impl<'a> Iterator for LexerState<'a> {
+    type Item = JsResult<Token<'a>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        //let c = self.next_char();
-
-        /*match c {
-            Some('a' ... 'z') | Some('A' ... 'Z') | Some('_') | Some('$') => {
-                self.reset_tmp();
-                self.tmp_push(c.unwrap());
-            }
-            Some('"') => {
-                self.reset_tmp();
+        self.goal = None;
+        match self.pull() {
+            Ok(token) => token.map(Ok),
+            Err(err) => {
+                self.update(LexerMode::EOF);
+                self.pending = false;
+                Some(Err(err))
             }
-            Some('\'') => {
-                self.reset_tmp();
-            }
-            Some('0') => {
-                self.reset_tmp();
-                self.tmp_push(c.unwrap());
-            }
-            Some('1'...'9') => {
-                self.reset_tmp();
-                self.tmp_push(c.unwrap());
-            }
-            Some('\n') |
-            Some('\r') => Some(TokenType::LineTerminate),
-            Some(' ') |
-            Some('\t') |
-            Some('\u{c}') |
-            Some('\u{b}') |
-            Some('\u{a0}') => {
-                if self.last_char_is_unicode() {
-                    let c = self.current_char();
-                    let err = self.error(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar(c.unwrap())));
-                    return Err(err);
-                }
-            },
-            Some(';') => Some(TokenType::Semicolon),
-            Some(',') => Some(TokenType::Comma),
-            Some('{') => Some(TokenType::Punctuator(Punctuator::LeftBrace)),
-            Some('}') => Some(TokenType::Punctuator(Punctuator::RightBrace)),
-            Some('[') => Some(TokenType::Punctuator(Punctuator::LeftBracket)),
-            Some(']') => Some(TokenType::Punctuator(Punctuator::RightBracket)),
-            Some('(') => Some(TokenType::Punctuator(Punctuator::LeftParen)),
-            Some(')') => Some(TokenType::Punctuator(Punctuator::RightParen)),
-            Some('~') => Some(TokenType::Punctuator(Punctuator::Tilde)),
-            Some(':') => Some(TokenType::Punctuator(Punctuator::Colon)),
-            Some('?') => Some(TokenType::Punctuator(Punctuator::QuestionMark)),
-            Some('.') => self.start_punctuator(Punctuator::Point),
-            Some('|') => self.start_punctuator(Punctuator::OrBitwise),
-            Some('*') => self.start_punctuator(Punctuator::Multiple),
-            Some('&') => self.start_punctuator(Punctuator::AndBitwise),
-            Some('^') => self.start_punctuator(Punctuator::Xor),
-            Some('+') => self.start_punctuator(Punctuator::Plus),
-            Some('-') => self.start_punctuator(Punctuator::Minus),
-            Some('%') => self.start_punctuator(Punctuator::Mod),
-            Some('=') => self.start_punctuator(Punctuator::Equal),
-            Some('<') => self.start_punctuator(Punctuator::SmallThan),
-            Some('/') => self.start_punctuator(Punctuator::Divide),
-            Some('!') => self.start_punctuator(Punctuator::Invert),
-            Some('>') => self.start_punctuator(Punctuator::GreaterThan),
-            None => {
-                self.update(LexerMode::EOF)
-            }
-            Some('\\') => {
-                let unicode = self.read_unicode();
-                match unicode {
-                    Some(c) => {
-                        println!("{:?}", c);
-                        self.overwrite_current_char_with_unicode(c);
-                        handled = false
-                    }
-                    _ => {
-                        panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
-                    }
-                }
-            }
-            _ => {
-                panic!("Unhandled Parser State Reached: {:?}, {:?}, {:?}, col {:?}, line {:?}", c, self.mode(), self.is_escaped(), self.col(), self.line());
-                //self.update(LexerMode::EOF);
-            }
-        }*/
-        None
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,26 +1,113 @@
 use lexer::enums::{TokenType};
+use std::borrow::Cow;
 use error::error::{CodePos};
+use lexer::unicode::is_line_terminator;
 
 
+/// A location in the source: byte offset plus 1-based line and column.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy, Default)]
+pub struct Position {
+    pub offset: usize,
+    pub line: u64,
+    pub col: u32
+}
+
+impl Position {
+    pub fn new(offset: usize, line: u64, col: u32) -> Position {
+        Position {
+            offset: offset,
+            line: line,
+            col: col
+        }
+    }
+
+    /// Moves the position past `c`.
+    pub fn advance(&mut self, c: char) {
+        self.offset += c.len_utf8();
+        if is_line_terminator(c) {
+            self.line += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy, Default)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Span {
+        Span {
+            start: start,
+            end: end
+        }
+    }
+}
+
+/// What kind of comment was read.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum CommentKind {
+    /// `// ...`
+    Line,
+    /// `/* ... */`
+    Block,
+    /// `#! ...` at the start of the input
+    Hashbang,
+    /// Annex B `<!-- ...` or `--> ...`
+    Html
+}
+
+/// A comment with its text between the delimiters, kept aside from the tokens.
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct Comment<'a> {
+    pub kind: CommentKind,
+    pub text: Cow<'a, str>,
+    pub span: Span
+}
+
+impl<'a> Comment<'a> {
+    pub fn new(kind: CommentKind, text: Cow<'a, str>, span: Span) -> Comment<'a> {
+        Comment {
+            kind: kind,
+            text: text,
+            span: span
+        }
+    }
+
+    pub fn into_owned(self) -> Comment<'static> {
+        Comment::new(self.kind, Cow::Owned(self.text.into_owned()), self.span)
+    }
+}
+
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
-pub struct Token {
-    pub token: TokenType,
-    pub col: u32,
-    pub line: u64
+pub struct Token<'a> {
+    pub token: TokenType<'a>,
+    pub span: Span,
+    /// Set when a line terminator comes between this token and the previous one, which is
+    /// what automatic semicolon insertion looks at.
+    pub newline_before: bool,
+    /// Set on a legacy octal number like `017` or `08` and on a string with a legacy octal
+    /// escape like `'\017'`, which the parser rejects in strict code.
+    pub legacy_octal: bool
 }
 
-impl Token {
-    pub fn new(token: TokenType, col: u32, line: u64) -> Token {
+impl<'a> Token<'a> {
+    pub fn new(token: TokenType<'a>, span: Span) -> Token<'a> {
         Token {
             token: token,
-            col: col,
-            line: line,
+            span: span,
+            newline_before: false,
+            legacy_octal: false
         }
     }
 }
 
-impl CodePos for Token {
-    fn location(&self) -> (u64, u32) {
-        (self.line, self.col)
+impl<'a> CodePos for Token<'a> {
+    fn span(&self) -> Span {
+        self.span
     }
 }
@@ -0,0 +1,61 @@
+//! Identifier classification, see `scripts/unicode.py` for how the tables are built.
+mod tables;
+
+use std::cmp::Ordering;
+
+fn search(table: &[(char, char)], c: char) -> bool {
+    table.binary_search_by(|&(lo, hi)| {
+        if c < lo {
+            Ordering::Greater
+        } else if c > hi {
+            Ordering::Less
+        } else {
+            Ordering::Equal
+        }
+    }).is_ok()
+}
+
+/// Unicode `ID_Start`.
+pub fn is_id_start(c: char) -> bool {
+    match c {
+        'a' ... 'z' | 'A' ... 'Z' => true,
+        '\u{0}' ... '\u{7f}' => false,
+        c => search(tables::ID_START, c)
+    }
+}
+
+/// Unicode `ID_Continue`.
+pub fn is_id_continue(c: char) -> bool {
+    match c {
+        'a' ... 'z' | 'A' ... 'Z' | '0' ... '9' | '_' => true,
+        '\u{0}' ... '\u{7f}' => false,
+        c => search(tables::ID_CONTINUE, c)
+    }
+}
+
+/// A char that may start an IdentifierName.
+pub fn is_identifier_start(c: char) -> bool {
+    c == '$' || c == '_' || is_id_start(c)
+}
+
+/// A char that may continue an IdentifierName, including ZWNJ and ZWJ.
+pub fn is_identifier_part(c: char) -> bool {
+    c == '$' || c == '\u{200c}' || c == '\u{200d}' || is_id_continue(c)
+}
+
+/// ECMAScript LineTerminator.
+pub fn is_line_terminator(c: char) -> bool {
+    match c {
+        '\n' | '\r' | '\u{2028}' | '\u{2029}' => true,
+        _ => false
+    }
+}
+
+/// ECMAScript WhiteSpace: tab, vertical tab, form feed, ZWNBSP and the `Zs` space separators.
+pub fn is_whitespace(c: char) -> bool {
+    match c {
+        '\t' | '\u{b}' | '\u{c}' | '\u{feff}' => true,
+        ' ' | '\u{a0}' | '\u{1680}' | '\u{2000}' ... '\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}' => true,
+        _ => false
+    }
+}
@@ -1,97 +1,99 @@
+use ast::node::{Node, Statement, Declaration};
 use error::JsResult;
-use error::error::{Error, ErrorType, SyntaxErrorType};
-use lexer::enums::{TokenType, LiteralType};
-use scope::parser::{Parser, Item};
+use lexer::enums::{TokenType, Contextual};
+use scope::parser::{Parser};
 
-macro_rules! wait {
-    ($expr:expr) => (match $expr {
-        Item::Item => return Ok(Item::Item),
-        Item::None => (),
-    })
-}
+impl<'a> Parser<'a> {
 
-
-macro_rules! none {
-    ($expr:expr) => (match $expr {
-        Item::None => return Ok(Item::None),
-        Item::Item => (),
-    })
-}
-
-impl Parser {
-
-    pub fn consume_all_lineterminates(&mut self) -> JsResult<()> {
-        println!("consume_all_lineterminates {:?}", self.peek());
-        while try!(self.consume(TokenType::LineTerminate)) {}
-        Ok(())
-    }
-
-    pub fn parse_stmt_list(&mut self) -> JsResult<()> {
+    pub fn parse_stmt_list(&mut self) -> JsResult<Vec<Node<Statement>>> {
+        let mut list = Vec::new();
         loop {
             println!("parse_stmt_list {:?}", self.peek());
             match self.peek() {
                 None |
                 Some(TokenType::RightBrace) |
                 Some(TokenType::Case) |
-                Some(TokenType::Default) => return Ok(()),
+                Some(TokenType::Default) => return Ok(list),
                 _ => {}
             }
 
+            // a `let` declaration may continue on the next line here, unlike in a single statement
+            if self.at_let_declaration() {
+                let declaration = try!(self.parse_declaration());
+                list.push(declaration);
+                continue
+            }
             match try!(self.parse_stmt()) {
-                Item::Item => (),
-                Item::None => {
-                    try!(self.parse_declaration());
+                Some(stmt) => list.push(stmt),
+                None => {
+                    let declaration = try!(self.parse_declaration());
+                    list.push(declaration);
                 },
             }
         }
     }
 
-    pub fn parse_labelled(&mut self) -> JsResult<Item> {
-        try!(self.bump());
+    pub fn parse_labelled(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
+        let label = try!(self.expect_identifier());
         try!(self.expect(TokenType::Colon));
-        match self.peek() {
-            Some(TokenType::Function) => self.parse_function(),
-            Some(_) => self.parse_stmt(),
-            None => {
-                return Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF), 0, 0, None))
-            }
-        }
+        let body = match self.peek() {
+            Some(TokenType::Function) => try!(self.parse_function_declaration()),
+            _ => try!(self.expect_stmt())
+        };
+        Ok(self.finish(start, Statement::Labeled {
+            label: label,
+            body: Box::new(body)
+        }))
     }
 
-    pub fn parse_block(&mut self) -> JsResult<Item> {
-        println!("parse_block {:?}", self.peek());
+    pub fn parse_block_body(&mut self) -> JsResult<Node<Vec<Node<Statement>>>> {
+        let start = self.position();
         try!(self.expect(TokenType::LeftBrace));
-        try!(self.parse_stmt_list());
+        let body = try!(self.parse_stmt_list());
         try!(self.expect(TokenType::RightBrace));
-        Ok(Item::Item)
+        Ok(self.finish(start, body))
     }
 
-    pub fn parse_empty(&mut self) -> JsResult<Item> {
+    pub fn parse_block(&mut self) -> JsResult<Node<Statement>> {
+        let body = try!(self.parse_block_body());
+        Ok(Node::new(body.span, Statement::Block(body.item)))
+    }
+
+    pub fn parse_empty(&mut self) -> JsResult<Node<Statement>> {
         println!("parse_empty {:?}", self.peek());
+        let start = self.position();
         try!(self.bump());
-        Ok(Item::Item)
+        Ok(self.finish(start, Statement::Empty))
+    }
+
+    pub fn parse_function_declaration(&mut self) -> JsResult<Node<Statement>> {
+        let function = try!(self.parse_function());
+        Ok(Node::new(function.span, Statement::Declaration(Declaration::Function(function.item))))
     }
 
-    pub fn parse_declaration(&mut self) -> JsResult<Item> {
+    pub fn parse_declaration(&mut self) -> JsResult<Node<Statement>> {
         println!("parse_declaration {:?}", self.peek());
         match self.peek() {
-            Some(TokenType::Function) => self.parse_function(),
+            Some(TokenType::Function) => self.parse_function_declaration(),
             Some(TokenType::Class) => self.parse_class(),
-            Some(TokenType::Let) => self.parse_let(),
+            Some(TokenType::Contextual(Contextual::Async)) if self.at_async_function() => self.parse_function_declaration(),
+            Some(TokenType::Contextual(Contextual::Let)) => self.parse_let(),
             Some(TokenType::Const) => self.parse_const(),
-            Some(TokenType::LineTerminate) => {
-                try!(self.bump());
-                println!("Warning for LineTerminate");
-                Ok(Item::None)
-            }
-            Some(t) => Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::Unexpected(t)), 0, 0, None)),
-            None => Ok(Item::None)
+            _ => self.expected(&["statement"])
+        }
+    }
+
+    pub fn expect_stmt(&mut self) -> JsResult<Node<Statement>> {
+        match try!(self.parse_stmt()) {
+            Some(stmt) => Ok(stmt),
+            None => self.expected(&["statement"])
         }
     }
 
-    pub fn parse_stmt(&mut self) -> JsResult<Item> {
+    pub fn parse_stmt(&mut self) -> JsResult<Option<Node<Statement>>> {
         println!("parse_stmt {:?}", self.peek());
-        match self.peek() {
+        let stmt = match self.peek() {
             Some(TokenType::LeftBrace) => self.parse_block(),
             Some(TokenType::Var) => self.parse_variable(),
             Some(TokenType::Semicolon) => self.parse_empty(),
@@ -111,27 +113,17 @@ impl Parser {
             Some(TokenType::Try) => self.parse_try(),
             Some(TokenType::Debugger) => self.parse_debugger(),
 
-            Some(TokenType::This) => self.parse_expr_stmt(),
-            Some(TokenType::Increment) => self.parse_expr_stmt(),
-            Some(TokenType::Decrement) => self.parse_expr_stmt(),
-            Some(TokenType::Delete) => self.parse_expr_stmt(),
-            Some(TokenType::LeftParen) => self.parse_expr_stmt(),
-            Some(TokenType::Minus) => self.parse_expr_stmt(),
-            Some(TokenType::Invert) => self.parse_expr_stmt(),
-            Some(TokenType::Plus) => self.parse_expr_stmt(),
-            Some(TokenType::Literal(LiteralType::String(_))) => self.parse_expr_stmt(),
-            Some(TokenType::Literal(LiteralType::Integer(_))) => self.parse_expr_stmt(),
-
-            Some(TokenType::Identifier(_)) => {
+            Some(TokenType::Identifier(_)) |
+            Some(TokenType::Contextual(_)) => {
                 if Some(TokenType::Colon) == self.peek_at(1) {
                     self.parse_labelled()
                 } else {
-                    self.parse_expr_stmt()
+                    return self.parse_expr_stmt()
                 }
             }
-            Some(TokenType::Yield) => self.parse_yield_expr(),
-            Some(_) => Ok(Item::None),
-            None => Ok(Item::None)
-        }
+            Some(_) => return self.parse_expr_stmt(),
+            None => return Ok(None)
+        };
+        stmt.map(Some)
     }
 }
\ No newline at end of file
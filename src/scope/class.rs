@@ -0,0 +1,267 @@
+use ast::node::{Node, Statement, Declaration, Expression, Class, ClassElement, MethodKind, PropertyKey};
+use error::JsResult;
+use error::error::SyntaxErrorType;
+use lexer::enums::{TokenType, LiteralType, Contextual};
+use lexer::token::{Position, Span};
+use scope::parser::{Parser, PrivateNames};
+
+/// Whether `key` is the non-computed property name `name`.
+fn has_name(key: &PropertyKey, name: &str) -> bool {
+    match *key {
+        PropertyKey::Identifier(ref key) => key == name,
+        PropertyKey::Literal(LiteralType::String(ref value, _)) => value == name,
+        _ => false
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn parse_class(&mut self) -> JsResult<Node<Statement>> {
+        let class = try!(self.parse_class_definition(true));
+        Ok(Node::new(class.span, Statement::Declaration(Declaration::Class(class.item))))
+    }
+
+    pub fn parse_class_expr(&mut self) -> JsResult<Option<Node<Expression>>> {
+        let class = try!(self.parse_class_definition(false));
+        Ok(Some(Node::new(class.span, Expression::Class(class.item))))
+    }
+
+    /// A class declaration has to be `named`, the name of a class expression is optional.
+    pub fn parse_class_definition(&mut self, named: bool) -> JsResult<Node<Class>> {
+        let start = self.position();
+        try!(self.expect(TokenType::Class));
+        // every part of a class is strict mode code
+        let outer = self.strict;
+        self.strict = true;
+        let id = if named {
+            Some(try!(self.expect_identifier()))
+        } else {
+            try!(self.consume_identifier())
+        };
+        let super_class = if try!(self.consume(TokenType::Extends)) {
+            match try!(self.parse_left_hand_side_expr()) {
+                Some(expr) => Some(Box::new(expr)),
+                None => return self.expected(&["expression"])
+            }
+        } else {
+            None
+        };
+        let body = try!(self.parse_class_body(super_class.is_some()));
+        self.strict = outer;
+        Ok(self.finish(start, Class {
+            id: id,
+            super_class: super_class,
+            body: body
+        }))
+    }
+
+    /// The constructor of a `derived` class may call `super()`.
+    pub fn parse_class_body(&mut self, derived: bool) -> JsResult<Node<Vec<Node<ClassElement>>>> {
+        let start = self.position();
+        try!(self.expect(TokenType::LeftBrace));
+        self.private_names.push(PrivateNames::default());
+        let mut elements = Vec::new();
+        let mut constructor = false;
+        loop {
+            match self.peek() {
+                Some(TokenType::Semicolon) => try!(self.bump()),
+                Some(TokenType::RightBrace) => break,
+                _ => {
+                    let element = try!(self.parse_class_element(derived));
+                    if let ClassElement::Method { kind: MethodKind::Constructor, ref key, .. } = element.item {
+                        if constructor {
+                            return self.error_at(SyntaxErrorType::InvalidConstructor, key.span, &[])
+                        }
+                        constructor = true;
+                    }
+                    elements.push(element);
+                }
+            }
+        }
+        try!(self.expect(TokenType::RightBrace));
+        try!(self.resolve_private_names());
+        Ok(self.finish(start, elements))
+    }
+
+    /// Declares `#name` in the innermost class body. Only a getter and a setter of the same
+    /// placement may share a name.
+    fn declare_private_name(&mut self, name: &str, span: Span, accessor: Option<MethodKind>, is_static: bool) -> JsResult<()> {
+        let previous = self.private_names.last().and_then(|names| names.declared.get(name).cloned());
+        let declared = match previous {
+            None => (accessor, is_static),
+            Some((Some(other), was_static)) if accessor.is_some() && accessor != Some(other) && was_static == is_static => {
+                (None, is_static)
+            }
+            Some(_) => return self.error_at(SyntaxErrorType::DuplicatePrivateName(String::from(name)), span, &[])
+        };
+        if let Some(names) = self.private_names.last_mut() {
+            names.declared.insert(String::from(name), declared);
+        }
+        Ok(())
+    }
+
+    /// Records a use of `#name`, which has to be declared by an enclosing class body.
+    pub fn use_private_name(&mut self, name: Node<String>) -> JsResult<()> {
+        match self.private_names.last_mut() {
+            Some(names) => {
+                names.used.push(name);
+                Ok(())
+            }
+            None => self.error_at(SyntaxErrorType::UndeclaredPrivateName(name.item), name.span, &[])
+        }
+    }
+
+    /// Ends the innermost class body, handing the names it does not declare to the one around it.
+    fn resolve_private_names(&mut self) -> JsResult<()> {
+        let names = match self.private_names.pop() {
+            Some(names) => names,
+            None => return Ok(())
+        };
+        for name in names.used {
+            if !names.declared.contains_key(&name.item) {
+                try!(self.use_private_name(name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the `static`, `async`, `get` or `set` ahead modifies the member after it instead of
+    /// being its name.
+    fn at_modifier(&mut self) -> bool {
+        match self.peek_at(1) {
+            Some(TokenType::LeftParen) |
+            Some(TokenType::Equal) |
+            Some(TokenType::Semicolon) |
+            Some(TokenType::RightBrace) |
+            None => false,
+            _ => true
+        }
+    }
+
+    pub fn parse_class_element(&mut self, derived: bool) -> JsResult<Node<ClassElement>> {
+        let start = self.position();
+        let is_static = self.peek_is(&TokenType::Contextual(Contextual::Static)) && self.at_modifier();
+        if is_static {
+            try!(self.bump());
+            if self.peek_is(&TokenType::LeftBrace) {
+                return self.parse_static_block(start)
+            }
+        }
+        // no line break is allowed between `async` and the name
+        let is_async = self.peek_is(&TokenType::Contextual(Contextual::Async)) && self.at_modifier() && !self.newline_before_at(1);
+        if is_async {
+            try!(self.bump());
+        }
+        let generator = try!(self.consume(TokenType::Multiple));
+        let kind = match self.peek() {
+            Some(TokenType::Contextual(Contextual::Get)) if !is_async && !generator && self.at_modifier() => MethodKind::Get,
+            Some(TokenType::Contextual(Contextual::Set)) if !is_async && !generator && self.at_modifier() => MethodKind::Set,
+            _ => MethodKind::Method
+        };
+        if kind != MethodKind::Method {
+            try!(self.bump());
+        }
+        let key = try!(self.parse_class_element_name());
+        if is_static && has_name(&key.item, "prototype") {
+            return self.error_at(SyntaxErrorType::StaticPrototype, key.span, &[])
+        }
+        if self.peek() != Some(TokenType::LeftParen) && kind == MethodKind::Method && !generator && !is_async {
+            return self.parse_field(start, key, is_static)
+        }
+        if let PropertyKey::Private(ref name) = key.item {
+            let accessor = if kind == MethodKind::Method { None } else { Some(kind) };
+            try!(self.declare_private_name(name, key.span, accessor, is_static));
+        }
+        let kind = if !is_static && has_name(&key.item, "constructor") {
+            if kind != MethodKind::Method || generator || is_async {
+                return self.error_at(SyntaxErrorType::InvalidConstructor, key.span, &[])
+            }
+            MethodKind::Constructor
+        } else {
+            kind
+        };
+        let value = match kind {
+            MethodKind::Get => try!(self.parse_accessor_method(false)),
+            MethodKind::Set => try!(self.parse_accessor_method(true)),
+            _ => try!(self.parse_method(generator, is_async, derived && kind == MethodKind::Constructor))
+        };
+        Ok(self.finish(start, ClassElement::Method {
+            key: key,
+            value: value,
+            kind: kind,
+            is_static: is_static
+        }))
+    }
+
+    /// A property name, or a `#name` that is not `#constructor`.
+    fn parse_class_element_name(&mut self) -> JsResult<Node<PropertyKey>> {
+        let start = self.position();
+        match self.peek() {
+            Some(TokenType::PrivateName(name)) => {
+                if name == "constructor" {
+                    return self.fatal(SyntaxErrorType::InvalidConstructor)
+                }
+                try!(self.bump());
+                Ok(self.finish(start, PropertyKey::Private(name.into_owned())))
+            }
+            _ => self.parse_property_name()
+        }
+    }
+
+    /// A field with an optional initializer, which is evaluated like a method body.
+    fn parse_field(&mut self, start: Position, key: Node<PropertyKey>, is_static: bool) -> JsResult<Node<ClassElement>> {
+        match key.item {
+            PropertyKey::Private(ref name) => try!(self.declare_private_name(name, key.span, None, is_static)),
+            ref key_item if has_name(key_item, "constructor") => {
+                return self.error_at(SyntaxErrorType::InvalidConstructor, key.span, &[])
+            }
+            _ => ()
+        }
+        let outer = (self.generator, self.is_async, self.static_block, self.class_init);
+        let meta = (self.super_property, self.super_call, self.new_target);
+        self.generator = false;
+        self.is_async = false;
+        self.static_block = false;
+        self.class_init = true;
+        self.super_property = true;
+        self.super_call = false;
+        self.new_target = true;
+        let value = try!(self.allow_in(|p| p.parse_initializer()));
+        self.generator = outer.0;
+        self.is_async = outer.1;
+        self.static_block = outer.2;
+        self.class_init = outer.3;
+        self.super_property = meta.0;
+        self.super_call = meta.1;
+        self.new_target = meta.2;
+        try!(self.consume_semicolon());
+        Ok(self.finish(start, ClassElement::Field {
+            key: key,
+            value: value,
+            is_static: is_static
+        }))
+    }
+
+    /// `static { ... }`, whose statements run once when the class is defined.
+    fn parse_static_block(&mut self, start: Position) -> JsResult<Node<ClassElement>> {
+        let outer = (self.generator, self.is_async, self.in_function, self.static_block, self.class_init);
+        self.generator = false;
+        self.is_async = false;
+        self.in_function = false;
+        let meta = (self.super_property, self.super_call, self.new_target);
+        self.static_block = true;
+        self.class_init = true;
+        self.super_property = true;
+        self.super_call = false;
+        self.new_target = true;
+        let body = try!(self.allow_in(|p| p.parse_block_body()));
+        self.generator = outer.0;
+        self.is_async = outer.1;
+        self.in_function = outer.2;
+        self.static_block = outer.3;
+        self.class_init = outer.4;
+        self.super_property = meta.0;
+        self.super_call = meta.1;
+        self.new_target = meta.2;
+        Ok(self.finish(start, ClassElement::StaticBlock(body.item)))
+    }
+}
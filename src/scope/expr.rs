@@ -1,235 +1,527 @@
+use ast::node::{Node, Statement, Expression, Pattern, UnaryOperator, UpdateOperator, AssignmentOperator};
+use lexer::token::{Position, Span};
 use error::JsResult;
-use error::error::{Error, ErrorType, SyntaxErrorType};
-use lexer::enums::{TokenType};
-use scope::parser::{Parser, Item};
+use lexer::enums::{TokenType, Contextual};
+use error::error::SyntaxErrorType;
+use scope::parser::{Parser};
 
 macro_rules! wait {
     ($expr:expr) => (match $expr {
-        Item::Item => return Ok(Item::Item),
-        Item::None => (),
+        Some(item) => return Ok(Some(item)),
+        None => (),
     })
 }
 
 
 macro_rules! none {
     ($expr:expr) => (match $expr {
-        Item::None => return Ok(Item::None),
-        Item::Item => (),
+        None => return Ok(None),
+        Some(item) => item,
     })
 }
 
-impl Parser {
-    pub fn expect_identifier(&mut self) -> JsResult<()> {
+pub fn identifier_name(token: &TokenType) -> Option<String> {
+    let name = match *token {
+        TokenType::Identifier(ref name) => return Some(name.to_string()),
+        TokenType::Var => "var",
+        TokenType::If => "if",
+        TokenType::Else => "else",
+        TokenType::Do => "do",
+        TokenType::Typeof => "typeof",
+        TokenType::Switch => "switch",
+        TokenType::Catch => "catch",
+        TokenType::Try => "try",
+        TokenType::Instanceof => "instanceof",
+        TokenType::Export => "export",
+        TokenType::Return => "return",
+        TokenType::Void => "void",
+        TokenType::Extends => "extends",
+        TokenType::Const => "const",
+        TokenType::Finally => "finally",
+        TokenType::Super => "super",
+        TokenType::With => "with",
+        TokenType::Delete => "delete",
+        TokenType::Default => "default",
+        TokenType::Function => "function",
+        TokenType::In => "in",
+        TokenType::For => "for",
+        TokenType::While => "while",
+        TokenType::Class => "class",
+        TokenType::Case => "case",
+        TokenType::Break => "break",
+        TokenType::Continue => "continue",
+        TokenType::New => "new",
+        TokenType::Throw => "throw",
+        TokenType::Debugger => "debugger",
+        TokenType::This => "this",
+        TokenType::Import => "import",
+        TokenType::Enum => "enum",
+        TokenType::Contextual(c) => c.name(),
+        _ => return None
+    };
+    Some(String::from(name))
+}
+
+/// The name of an identifier token, including contextual keywords.
+pub fn identifier(token: &TokenType) -> Option<String> {
+    match *token {
+        TokenType::Identifier(ref name) => Some(name.to_string()),
+        TokenType::Contextual(c) => Some(String::from(c.name())),
+        _ => None
+    }
+}
+
+impl<'a> Parser<'a> {
+    /// Whether `name` can not be used as an identifier here. Escaped keywords are lexed as
+    /// identifiers and are caught here too.
+    pub fn is_reserved(&self, name: &str) -> bool {
+        if TokenType::keyword(name).is_some() {
+            return true
+        }
+        match Contextual::from_name(name) {
+            Some(Contextual::Yield) => self.strict || self.generator,
+            Some(Contextual::Await) => self.module || self.is_async || self.static_block,
+            Some(c) => self.strict && c.is_strict_reserved(),
+            None => false
+        }
+    }
+
+    pub fn check_identifier(&mut self, name: &str, span: Span) -> JsResult<()> {
+        if self.is_reserved(name) {
+            return self.error_at(SyntaxErrorType::ReservedWord(String::from(name)), span, &[])
+        }
+        if self.class_init && name == "arguments" {
+            return self.error_at(SyntaxErrorType::InvalidArguments, span, &[])
+        }
+        Ok(())
+    }
+
+    /// The name of the next token when it is an identifier that is not reserved here.
+    pub fn peek_identifier(&mut self) -> JsResult<Option<String>> {
+        let name = match self.peek().as_ref().and_then(identifier) {
+            Some(name) => name,
+            None => return Ok(None)
+        };
+        let span = self.next_span();
+        try!(self.check_identifier(&name, span));
+        Ok(Some(name))
+    }
+
+    pub fn expect_identifier(&mut self) -> JsResult<Node<String>> {
         println!("expect_identifier {:?}", self.peek());
-        match self.peek() {
-            Some(TokenType::Identifier(_)) => {
+        let start = self.position();
+        match try!(self.peek_identifier()) {
+            Some(name) => {
                 try!(self.bump());
-                Ok(())
+                Ok(self.finish(start, name))
             }
-            _ => Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF), 0, 0, None))
+            None => self.expected(&["identifier"])
         }
     }
 
-    pub fn expect_identifier_name(&mut self) -> JsResult<()> {
+    pub fn expect_identifier_name(&mut self) -> JsResult<Node<String>> {
         println!("expect_identifier {:?}", self.peek());
-        match self.peek() {
-            Some(TokenType::Identifier(_)) => {
-                try!(self.bump());
-                Ok(())
-            }
-            Some(TokenType::Get) => {
-                try!(self.bump());
-                Ok(())
-            }
-            Some(TokenType::Set) => {
+        let start = self.position();
+        match self.peek().as_ref().and_then(identifier_name) {
+            Some(name) => {
                 try!(self.bump());
-                Ok(())
+                Ok(self.finish(start, name))
             }
-            _ => Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF), 0, 0, None))
+            None => self.expected(&["identifier"])
         }
     }
 
-    pub fn consume_identifier(&mut self) -> JsResult<bool> {
+    pub fn consume_identifier(&mut self) -> JsResult<Option<Node<String>>> {
         println!("consume_identifier {:?}", self.peek());
-        match self.peek() {
-            Some(TokenType::Identifier(_)) => {
+        let start = self.position();
+        match try!(self.peek_identifier()) {
+            Some(name) => {
                 try!(self.bump());
-                Ok(true)
+                Ok(Some(self.finish(start, name)))
             }
-            _ => Ok(false)
+            None => Ok(None)
         }
     }
 
-    pub fn parse_expr_stmt(&mut self) -> JsResult<Item> {
+    pub fn parse_expr_stmt(&mut self) -> JsResult<Option<Node<Statement>>> {
         println!("parse_expr_stmt {:?}", self.peek());
-        let result = match self.peek() {
-            Some(TokenType::LeftBrace) => Ok(Item::None),
-            Some(TokenType::Function) => Ok(Item::None),
-            Some(TokenType::Class) => Ok(Item::None),
-            Some(TokenType::Let) => {
-                if self.peek_at(1) == Some(TokenType::LeftBracket) {
-                    Ok(Item::None)
-                } else {
-                    Ok(Item::Item)
-                }
-            },
-            Some(_) => Ok(Item::Item),
-            None => Ok(Item::None),
+        match self.peek() {
+            Some(TokenType::LeftBrace) |
+            Some(TokenType::Function) |
+            Some(TokenType::Class) |
+            Some(TokenType::Const) |
+            None => return Ok(None),
+            Some(_) => (),
         };
-        none!(try!(result));
-        try!(self.parse_expr());
-        if try!(self.consume(TokenType::LineTerminate)) {
-            return  Ok(Item::Item)
+        // where only a statement is allowed, `let` and a line break are an identifier followed
+        // by an inserted semicolon
+        let declaration = self.at_let_declaration() &&
+            (!self.newline_before_at(1) || self.peek_at(1) == Some(TokenType::LeftBracket));
+        if declaration || self.at_async_function() {
+            return Ok(None)
         }
-        try!(self.expect(TokenType::Semicolon));
-        Ok(Item::Item)
+        let start = self.position();
+        let expr = try!(self.parse_expr());
+        try!(self.consume_semicolon());
+        Ok(Some(self.finish(start, Statement::Expression(expr))))
     }
 
-    pub fn parse_yield_expr(&mut self) -> JsResult<Item> {
+    pub fn parse_yield_expr(&mut self) -> JsResult<Option<Node<Expression>>> {
         println!("parse_yield_expr {:?}", self.peek());
-        if !try!(self.consume(TokenType::Yield)) {
-            return Ok(Item::None)
+        let start = self.position();
+        let span = self.next_span();
+        if !self.generator || !try!(self.consume(TokenType::Contextual(Contextual::Yield))) {
+            return Ok(None)
         }
-        if try!(self.consume(TokenType::Semicolon)) {
-            Ok(Item::Item)
-        } else {
-            try!(self.consume(TokenType::Multiple));
-            try!(self.parse_assign_expr());
-            try!(self.expect(TokenType::Semicolon));
-            Ok(Item::Item)
+        self.yield_await = self.yield_await.take().or(Some(Node::new(span, "yield")));
+        let argument = match self.peek() {
+            Some(TokenType::Semicolon) |
+            Some(TokenType::RightParen) |
+            Some(TokenType::RightBracket) |
+            Some(TokenType::RightBrace) |
+            Some(TokenType::Colon) |
+            Some(TokenType::Comma) |
+            None => false,
+            // the argument has to start on the same line
+            _ => !self.newline_before()
+        };
+        if !argument {
+            return Ok(Some(self.finish(start, Expression::Yield {
+                argument: None,
+                delegate: false
+            })))
         }
+        let delegate = try!(self.consume(TokenType::Multiple));
+        let argument = try!(self.expect_assign_expr());
+        Ok(Some(self.finish(start, Expression::Yield {
+            argument: Some(Box::new(argument)),
+            delegate: delegate
+        })))
     }
 
-    pub fn bump_and_return_item(&mut self) -> JsResult<Item> {
+    pub fn parse_identifier_reference(&mut self, name: String) -> JsResult<Option<Node<Expression>>> {
+        let start = self.position();
         try!(self.bump());
-        Ok(Item::Item)
+        if self.peek() == Some(TokenType::Lamda) {
+            let param = self.finish(start, Pattern::Identifier(name));
+            return self.parse_arrow_function(start, vec![param], false).map(Some)
+        }
+        Ok(Some(self.finish(start, Expression::Identifier(name))))
     }
 
-    pub fn parse_primary_expr(&mut self) -> JsResult<Item> {
+    pub fn parse_primary_expr(&mut self) -> JsResult<Option<Node<Expression>>> {
         println!("parse_primary_expr {:?}", self.peek());
+        let start = self.position();
         match self.peek() {
-            Some(TokenType::This) => self.bump_and_return_item(),
-            Some(TokenType::Identifier(_)) => self.bump_and_return_item(),
-            Some(TokenType::Literal(_)) => self.bump_and_return_item(),
-            Some(TokenType::LeftBracket) => self.parse_array_literal(),
-            Some(TokenType::LeftBrace) => self.parse_object_literal(),
-            Some(TokenType::Function) => self.parse_function_expr(),
-            Some(TokenType::Yield) => self.parse_yield_expr(),
-            Some(TokenType::LeftParen) => self.parse_cover_parenthesized_expression_and_arrow_parameter_list(),
-            _ => Ok(Item::None)
+            Some(TokenType::This) => {
+                try!(self.bump());
+                Ok(Some(self.finish(start, Expression::This)))
+            }
+            // `yield` is parsed by `parse_yield_expr` in generators
+            Some(TokenType::Contextual(Contextual::Yield)) if self.generator => Ok(None),
+            // and `await` by `parse_unary_expr` in async functions
+            Some(TokenType::Contextual(Contextual::Await)) if self.is_async => Ok(None),
+            Some(TokenType::Contextual(Contextual::Async)) if self.at_async_function() => {
+                self.allow_in(|p| p.parse_function_expr())
+            }
+            Some(TokenType::Contextual(Contextual::Async)) if self.at_async_arrow() => self.parse_async_arrow().map(Some),
+            Some(TokenType::Identifier(_)) |
+            Some(TokenType::Contextual(_)) => {
+                let name = try!(self.peek_identifier()).unwrap();
+                self.parse_identifier_reference(name)
+            }
+            Some(TokenType::Literal(literal)) => {
+                try!(self.bump());
+                Ok(Some(self.finish(start, Expression::Literal(literal.into_owned()))))
+            }
+            // `#name in object`, `parse_binary_expr` makes sure the `in` applies to it
+            Some(TokenType::PrivateName(name)) => {
+                if self.peek_at(1) != Some(TokenType::In) {
+                    return self.unexpected()
+                }
+                try!(self.bump());
+                let name = self.finish(start, name.into_owned());
+                try!(self.use_private_name(name.clone()));
+                Ok(Some(Node::new(name.span, Expression::PrivateName(name.item))))
+            }
+            Some(TokenType::NoSubstitutionTemplate(_)) |
+            Some(TokenType::TemplateHead(_)) => {
+                let quasi = try!(self.allow_in(|p| p.parse_template_literal(false)));
+                Ok(Some(Node::new(quasi.span, Expression::Template(quasi.item))))
+            }
+            Some(TokenType::Divide) | Some(TokenType::DivideAssign) => {
+                try!(self.rescan_regex());
+                self.parse_primary_expr()
+            }
+            Some(TokenType::LeftBracket) => self.allow_in(|p| p.parse_array_literal()),
+            Some(TokenType::LeftBrace) => self.allow_in(|p| p.parse_object_literal()),
+            Some(TokenType::Function) => self.allow_in(|p| p.parse_function_expr()),
+            Some(TokenType::Class) => self.allow_in(|p| p.parse_class_expr()),
+            Some(TokenType::LeftParen) => self.parse_cover_parenthesized_expression_and_arrow_parameter_list().map(Some),
+            _ => Ok(None)
         }
     }
 
-    pub fn dump_and_parse_unary_expr(&mut self) -> JsResult<Item> {
+    pub fn dump_and_parse_unary_expr(&mut self, operator: UnaryOperator) -> JsResult<Option<Node<Expression>>> {
+        let start = self.position();
         try!(self.bump());
-        self.parse_unary_expr(Item::None)
+        let argument = try!(self.expect_unary_expr());
+        Ok(Some(self.finish(start, Expression::Unary {
+            operator: operator,
+            argument: Box::new(argument)
+        })))
+    }
+
+    pub fn expect_unary_expr(&mut self) -> JsResult<Node<Expression>> {
+        match try!(self.parse_unary_expr(None)) {
+            Some(expr) => Ok(expr),
+            None => self.expected(&["expression"])
+        }
     }
 
-    pub fn parse_unary_expr(&mut self, first: Item) -> JsResult<Item> {
+    /// `first` is an operand parsed already, with its start including any parentheses.
+    pub fn parse_unary_expr(&mut self, first: Option<(Position, Node<Expression>)>) -> JsResult<Option<Node<Expression>>> {
         println!("parse_unary_expr {:?}", self.peek());
         wait!(try!(self.parse_update_expr(first)));
         match self.peek() {
-            Some(TokenType::Delete) => self.dump_and_parse_unary_expr(),
-            Some(TokenType::Void) => self.dump_and_parse_unary_expr(),
-            Some(TokenType::Typeof) => self.dump_and_parse_unary_expr(),
-            Some(TokenType::Plus) => self.dump_and_parse_unary_expr(),
-            Some(TokenType::Minus) => self.dump_and_parse_unary_expr(),
-            Some(TokenType::Tilde) => self.dump_and_parse_unary_expr(),
-            Some(TokenType::Invert) => self.dump_and_parse_unary_expr(),
-            _ => Ok(Item::None)
+            Some(TokenType::Delete) => {
+                let delete = try!(self.dump_and_parse_unary_expr(UnaryOperator::Delete));
+                if let Some(Node { item: Expression::Unary { ref argument, .. }, .. }) = delete {
+                    if let Expression::Member { ref property, .. } = argument.item {
+                        if let Expression::PrivateName(_) = property.item {
+                            return self.error_at(SyntaxErrorType::DeletePrivateName, argument.span, &[])
+                        }
+                    }
+                }
+                Ok(delete)
+            }
+            Some(TokenType::Void) => self.dump_and_parse_unary_expr(UnaryOperator::Void),
+            Some(TokenType::Typeof) => self.dump_and_parse_unary_expr(UnaryOperator::Typeof),
+            Some(TokenType::Plus) => self.dump_and_parse_unary_expr(UnaryOperator::Plus),
+            Some(TokenType::Minus) => self.dump_and_parse_unary_expr(UnaryOperator::Minus),
+            Some(TokenType::Tilde) => self.dump_and_parse_unary_expr(UnaryOperator::Tilde),
+            Some(TokenType::Invert) => self.dump_and_parse_unary_expr(UnaryOperator::Invert),
+            Some(TokenType::Contextual(Contextual::Await)) if self.is_async => {
+                let start = self.position();
+                let span = self.next_span();
+                try!(self.bump());
+                self.yield_await = self.yield_await.take().or(Some(Node::new(span, "await")));
+                let argument = try!(self.expect_unary_expr());
+                Ok(Some(self.finish(start, Expression::Await(Box::new(argument)))))
+            }
+            _ => Ok(None)
         }
     }
-    pub fn parse_update_expr(&mut self, first: Item) -> JsResult<Item> {
+
+    pub fn parse_update_expr(&mut self, first: Option<(Position, Node<Expression>)>) -> JsResult<Option<Node<Expression>>> {
         println!("parse_update_expr {:?}", self.peek());
-        let left = match first {
-            Item::Item => Item::Item,
-            Item::None => try!(self.parse_left_hand_side_expr())
+        let (start, left) = match first {
+            Some((start, expr)) => (start, Some(expr)),
+            None => (self.position(), try!(self.parse_left_hand_side_expr()))
         };
+        let operator = match self.peek() {
+            // a postfix operator has to be on the same line, otherwise it starts the next statement
+            _ if left.is_some() && self.newline_before() => return Ok(left),
+            Some(TokenType::Increment) => UpdateOperator::Increment,
+            Some(TokenType::Decrement) => UpdateOperator::Decrement,
+            _ => return Ok(left)
+        };
+        try!(self.bump());
         match left {
-            Item::None => {
-                if try!(self.consume(TokenType::Increment)) {
-                    return self.parse_unary_expr(Item::None);
-                }
-                if try!(self.consume(TokenType::Decrement)) {
-                    return self.parse_unary_expr(Item::None);
-                }
-                return Ok(Item::None)
+            None => {
+                let argument = try!(self.expect_unary_expr());
+                try!(self.check_simple_assignment_target(&argument));
+                Ok(Some(self.finish(start, Expression::Update {
+                    operator: operator,
+                    prefix: true,
+                    argument: Box::new(argument)
+                })))
             },
-            Item::Item => {
-                if try!(self.consume(TokenType::Increment)) {
-                    return Ok(Item::Item)
-                }
-                if try!(self.consume(TokenType::Decrement)) {
-                    return Ok(Item::Item)
-                }
-                return Ok(Item::Item)
+            Some(argument) => {
+                try!(self.check_simple_assignment_target(&argument));
+                Ok(Some(self.finish(start, Expression::Update {
+                    operator: operator,
+                    prefix: false,
+                    argument: Box::new(argument)
+                })))
             }
         }
     }
 
-    pub fn parse_conditional_expr(&mut self, first: Item) -> JsResult<Item> {
+    pub fn parse_conditional_expr(&mut self, first: Option<(Position, Node<Expression>)>) -> JsResult<Option<Node<Expression>>> {
         println!("parse_conditional_expr {:?}", self.peek());
-        none!(try!(self.parse_logical_expr(first)));
-        if try!(self.consume(TokenType::QuestionMark)) {
-            try!(self.parse_assign_expr());
-            try!(self.expect(TokenType::Colon));
-            try!(self.parse_assign_expr());
+        let start = match first {
+            Some((start, _)) => start,
+            None => self.position()
+        };
+        let test = none!(try!(self.parse_logical_expr(first)));
+        if !try!(self.consume_continuation(TokenType::QuestionMark)) {
+            return Ok(Some(test))
         }
-        Ok(Item::Item)
+        // `in` is always an operator between `?` and `:`
+        let consequent = try!(self.allow_in(|p| p.expect_assign_expr()));
+        try!(self.expect(TokenType::Colon));
+        let alternate = try!(self.expect_assign_expr());
+        Ok(Some(self.finish(start, Expression::Conditional {
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate: Box::new(alternate)
+        })))
     }
 
-    pub fn parse_cover_parenthesized_expression_and_arrow_parameter_list(&mut self) -> JsResult<Item> {
+    pub fn parse_cover_parenthesized_expression_and_arrow_parameter_list(&mut self) -> JsResult<Node<Expression>> {
         println!("parse_cover_parenthesized_expression_and_arrow_parameter_list {:?}", self.peek());
+        let start = self.position();
         try!(self.expect(TokenType::LeftParen));
-        if !try!(self.consume(TokenType::ThreePoints)) {
-            try!(self.parse_expr());
-            try!(self.consume(TokenType::ThreePoints));
+        let outer = self.start_cover();
+        let outer_parameters = self.start_parameters();
+        let mut items = Vec::new();
+        let mut rest = None;
+        let mut trailing_comma = false;
+        let first_start = self.position();
+        loop {
+            if try!(self.consume(TokenType::RightParen)) {
+                break
+            }
+            let rest_start = self.position();
+            if try!(self.consume(TokenType::ThreePoints)) {
+                let target = try!(self.allow_in(|p| p.parse_binding_target()));
+                rest = Some(self.finish(rest_start, Pattern::Rest(Box::new(target))));
+                try!(self.expect(TokenType::RightParen));
+                break
+            }
+            items.push(try!(self.allow_in(|p| p.expect_assign_expr_or_pattern())));
+            trailing_comma = try!(self.consume(TokenType::Comma));
+            if !trailing_comma {
+                try!(self.expect(TokenType::RightParen));
+                break
+            }
+        }
+
+        if self.peek() == Some(TokenType::Lamda) {
+            let mut params = Vec::new();
+            for item in items {
+                params.push(try!(self.arrow_parameter(item)));
+            }
+            try!(self.end_cover(outer));
+            try!(self.end_parameters(outer_parameters));
+            if let Some(rest) = rest {
+                params.push(rest);
+            }
+            return self.parse_arrow_function(start, params, false)
+        }
+
+        if items.is_empty() || rest.is_some() || trailing_comma {
+            return self.expected(&["`=>`"])
         }
-        try!(self.consume_all_lineterminates());
-        try!(self.expect(TokenType::RightParen));
-        Ok(Item::Item)
+        try!(self.end_cover(outer));
+        self.merge_parameters(outer_parameters);
+        let expr = if items.len() == 1 {
+            items.remove(0)
+        } else {
+            let span = Span::new(first_start, items[items.len() - 1].span.end);
+            Node::new(span, Expression::Sequence(items))
+        };
+        // `(a) = 1` is allowed, `({ a }) = 1` and `((a)) => 1` are not
+        match expr.item {
+            Expression::Identifier(_) | Expression::Member { .. } => {
+                self.cover.parenthesized_simple = self.cover.parenthesized_simple.or(Some(expr.span))
+            }
+            _ => self.cover.parenthesized = self.cover.parenthesized.or(Some(expr.span))
+        }
+        Ok(expr)
+    }
+
+    pub fn expect_assign_expr(&mut self) -> JsResult<Node<Expression>> {
+        match try!(self.parse_assign_expr()) {
+            Some(expr) => Ok(expr),
+            None => self.expected(&["expression"])
+        }
+    }
+
+    pub fn parse_assign_expr(&mut self) -> JsResult<Option<Node<Expression>>> {
+        let outer = self.start_cover();
+        let expr = try!(self.parse_assignment());
+        try!(self.end_cover(outer));
+        Ok(expr)
+    }
+
+    /// An element of an array or object literal or a parenthesized expression, which may still
+    /// become part of a pattern. The caller settles its cover errors.
+    pub fn parse_assign_expr_or_pattern(&mut self) -> JsResult<Option<Node<Expression>>> {
+        let outer = self.start_cover();
+        let expr = try!(self.parse_assignment());
+        self.merge_cover(outer);
+        Ok(expr)
     }
 
-    pub fn parse_assign_expr(&mut self) -> JsResult<Item> {
+    pub fn expect_assign_expr_or_pattern(&mut self) -> JsResult<Node<Expression>> {
+        match try!(self.parse_assign_expr_or_pattern()) {
+            Some(expr) => Ok(expr),
+            None => self.expected(&["expression"])
+        }
+    }
+
+    fn parse_assignment(&mut self) -> JsResult<Option<Node<Expression>>> {
         println!("parse_assign_expr {:?}", self.peek());
+        let start = self.position();
         match try!(self.parse_left_hand_side_expr()) {
-            Item::Item => {
+            Some(left) => {
                 println!(" parse_assign_expr {:?}", self.peek());
-                match self.peek() {
-                    Some(TokenType::DivideAssign) |
-                    Some(TokenType::ExpAssign) |
-                    Some(TokenType::LeftShiftAssign) |
-                    Some(TokenType::ModAssign) |
-                    Some(TokenType::PlusAssign) |
-                    Some(TokenType::MinusAssign) |
-                    Some(TokenType::MultipleAssign) |
-                    Some(TokenType::OrBitwiseAssign) |
-                    Some(TokenType::XorAssign) |
-                    Some(TokenType::AndBitwiseAssign) |
-                    Some(TokenType::Equal) => {
-                        try!(self.bump());
-                        self.parse_assign_expr()
-                    }
+                let operator = match self.peek() {
+                    Some(TokenType::DivideAssign) => AssignmentOperator::DivideAssign,
+                    Some(TokenType::ExpAssign) => AssignmentOperator::ExpAssign,
+                    Some(TokenType::LeftShiftAssign) => AssignmentOperator::LeftShiftAssign,
+                    Some(TokenType::RightShiftAssign) => AssignmentOperator::RightShiftAssign,
+                    Some(TokenType::RightShiftUnsignedAssign) => AssignmentOperator::RightShiftUnsignedAssign,
+                    Some(TokenType::ModAssign) => AssignmentOperator::ModAssign,
+                    Some(TokenType::PlusAssign) => AssignmentOperator::PlusAssign,
+                    Some(TokenType::MinusAssign) => AssignmentOperator::MinusAssign,
+                    Some(TokenType::MultipleAssign) => AssignmentOperator::MultipleAssign,
+                    Some(TokenType::OrBitwiseAssign) => AssignmentOperator::OrBitwiseAssign,
+                    Some(TokenType::XorAssign) => AssignmentOperator::XorAssign,
+                    Some(TokenType::AndBitwiseAssign) => AssignmentOperator::AndBitwiseAssign,
+                    Some(TokenType::Equal) => AssignmentOperator::Equal,
                     _ => {
-                        self.parse_conditional_expr(Item::Item)
+                        return self.parse_conditional_expr(Some((start, left)))
                     }
-                }
+                };
+                try!(self.bump());
+                let left = if operator == AssignmentOperator::Equal {
+                    try!(self.assignment_target(left))
+                } else {
+                    try!(self.simple_assignment_target(left))
+                };
+                let right = try!(self.expect_assign_expr());
+                Ok(Some(self.finish(start, Expression::Assignment {
+                    operator: operator,
+                    left: Box::new(left),
+                    right: Box::new(right)
+                })))
             }
-            Item::None => {
-                wait!(try!(self.parse_conditional_expr(Item::None)));
+            None => {
+                wait!(try!(self.parse_conditional_expr(None)));
                 self.parse_yield_expr()
             }
         }
     }
 
-    pub fn parse_expr(&mut self) -> JsResult<Item> {
-        try!(self.consume_all_lineterminates());
+    pub fn parse_expr(&mut self) -> JsResult<Node<Expression>> {
         println!("parse_expr {:?}", self.peek());
-        try!(self.parse_assign_expr());
-        while try!(self.consume(TokenType::Comma)) {
-            try!(self.consume_all_lineterminates());
-            try!(self.parse_assign_expr());
+        let start = self.position();
+        let first = try!(self.expect_assign_expr());
+        self.parse_sequence(start, first)
+    }
+
+    /// The comma separated expressions after `first`, which starts at `start`, if there are any.
+    pub fn parse_sequence(&mut self, start: Position, first: Node<Expression>) -> JsResult<Node<Expression>> {
+        if self.peek() != Some(TokenType::Comma) {
+            return Ok(first)
+        }
+        let mut expressions = vec![first];
+        while try!(self.consume_continuation(TokenType::Comma)) {
+            expressions.push(try!(self.expect_assign_expr()));
         }
-        Ok(Item::Item)
+        Ok(self.finish(start, Expression::Sequence(expressions)))
     }
 }
@@ -1,59 +1,228 @@
+use ast::node::{Node, Statement, Expression, Function, Pattern, ArrowBody};
 use error::JsResult;
-//use error::error::{Error, ErrorType, SyntaxErrorType};
-use lexer::enums::{TokenType};
-use scope::parser::{Parser, Item};
-
-macro_rules! wait {
-    ($expr:expr) => (match $expr {
-        Item::Item => return Ok(Item::Item),
-        Item::None => (),
-    })
-}
+use error::error::SyntaxErrorType;
+use lexer::enums::{TokenType, Contextual};
+use scope::expr::identifier;
+use scope::pattern::bound_names;
+use lexer::token::Position;
+use scope::parser::{Parser};
 
+impl<'a> Parser<'a> {
+    pub fn parse_function(&mut self) -> JsResult<Node<Function>> {
+        println!("parse_function {:?}", self.peek());
+        let start = self.position();
+        let is_async = self.at_async_function();
+        if is_async {
+            try!(self.bump());
+        }
+        try!(self.expect(TokenType::Function));
+        let generator = try!(self.consume(TokenType::Multiple));
+        let id = try!(self.expect_identifier());
+        let (params, body) = try!(self.parse_function_rest(generator, is_async, false, false));
+        Ok(self.finish(start, Function {
+            id: Some(id),
+            params: params,
+            body: body,
+            generator: generator,
+            is_async: is_async
+        }))
+    }
 
-macro_rules! none {
-    ($expr:expr) => (match $expr {
-        Item::None => return Ok(Item::None),
-        Item::Item => (),
-    })
-}
+    /// Whether the next tokens are `async function`, which can not be split over two lines.
+    pub fn at_async_function(&mut self) -> bool {
+        self.peek_is(&TokenType::Contextual(Contextual::Async)) &&
+            self.peek_at(1) == Some(TokenType::Function) && !self.newline_before_at(1)
+    }
 
-impl Parser {
-    pub fn parse_function(&mut self) -> JsResult<Item> {
-        println!("parse_function {:?}", self.peek());
-        if !try!(self.consume(TokenType::Function)) {
-            return Ok(Item::None)
+    /// Whether the next tokens are `async x =>`, the async arrow function with a single
+    /// parameter. The parenthesized form is told apart from a call in `parse_call_expr`.
+    pub fn at_async_arrow(&mut self) -> bool {
+        self.peek_is(&TokenType::Contextual(Contextual::Async)) && !self.newline_before_at(1) &&
+            self.peek_at(1).as_ref().and_then(identifier).is_some() && self.peek_at(2) == Some(TokenType::Lamda)
+    }
+
+    pub fn parse_async_arrow(&mut self) -> JsResult<Node<Expression>> {
+        let start = self.position();
+        try!(self.bump());
+        // `await` is reserved in the parameters too
+        let outer = self.is_async;
+        self.is_async = true;
+        let param = self.expect_identifier();
+        self.is_async = outer;
+        let param = try!(param);
+        let param = Node::new(param.span, Pattern::Identifier(param.item));
+        self.parse_arrow_function(start, vec![param], true)
+    }
+
+    /// Turns the arguments of `async(...)` into the parameters of an async arrow function.
+    pub fn arguments_to_params(&mut self, arguments: Vec<Node<Expression>>) -> JsResult<Vec<Node<Pattern>>> {
+        let mut params = Vec::new();
+        let count = arguments.len();
+        for (index, argument) in arguments.into_iter().enumerate() {
+            let param = match argument {
+                Node { span, item: Expression::Spread(argument) } => {
+                    let argument = try!(self.arrow_parameter(*argument));
+                    try!(self.rest_element(span, argument, index + 1 == count))
+                }
+                argument => try!(self.arrow_parameter(argument))
+            };
+            params.push(param);
         }
-        try!(self.consume(TokenType::Multiple));
-        try!(self.consume_identifier());
-        try!(self.parse_formal_parameters());
-        self.parse_block()
+        Ok(params)
     }
 
-    pub fn parse_formals_list(&mut self) -> JsResult<Item> {
-        try!(self.consume_identifier());
-        Ok(Item::Item)
+    /// Parses the parameters and body of a function, with `yield` as a keyword in generators
+    /// and `await` in async functions. The parameters of a `method` have to be unique, its body
+    /// may use `super.x` and, in the constructor of a derived class, `super_call`.
+    pub fn parse_function_rest(&mut self, generator: bool, is_async: bool, method: bool, super_call: bool) -> JsResult<(Vec<Node<Pattern>>, Node<Vec<Node<Statement>>>)> {
+        let outer = (self.generator, self.is_async, self.strict);
+        let class = (self.in_function, self.static_block, self.class_init);
+        let meta = (self.super_property, self.super_call, self.new_target);
+        self.generator = generator;
+        self.is_async = is_async;
+        self.in_function = true;
+        self.static_block = false;
+        self.class_init = false;
+        self.super_property = method;
+        self.super_call = super_call;
+        self.new_target = true;
+        let outer_parameters = self.start_parameters();
+        let params = try!(self.parse_formal_parameters());
+        try!(self.end_parameters(None));
+        let body = try!(self.parse_function_body());
+        // checked after the body, which may make the function strict
+        try!(self.check_parameters(&params, method));
+        self.generator = outer.0;
+        self.is_async = outer.1;
+        self.strict = outer.2;
+        self.in_function = class.0;
+        self.static_block = class.1;
+        self.class_init = class.2;
+        self.super_property = meta.0;
+        self.super_call = meta.1;
+        self.new_target = meta.2;
+        self.yield_await = outer_parameters;
+        Ok((params, body))
     }
 
-    pub fn parse_formal_parameters(&mut self) -> JsResult<Item> {
-        try!(self.expect(TokenType::LeftParen));
-        try!(self.parse_formals_list());
-        while try!(self.consume(TokenType::Comma)) {
-            try!(self.parse_formals_list());
+    /// Fails on a name bound by two parameters, which only a sloppy function with simple
+    /// parameters allows unless they have to be `unique`.
+    pub fn check_parameters(&mut self, params: &[Node<Pattern>], unique: bool) -> JsResult<()> {
+        let simple = params.iter().all(|param| match param.item {
+            Pattern::Identifier(_) => true,
+            _ => false
+        });
+        if simple && !unique && !self.strict {
+            return Ok(())
         }
-        try!(self.expect(TokenType::RightParen));
-        Ok(Item::Item)
+        let mut names = Vec::new();
+        for param in params {
+            bound_names(param, &mut names);
+        }
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].iter().any(|other| other.item == name.item) {
+                return self.error_at(SyntaxErrorType::DuplicateParameter(String::from(name.item)), name.span, &[])
+            }
+        }
+        Ok(())
+    }
+
+    /// A block body that may start with a `"use strict"` directive.
+    pub fn parse_function_body(&mut self) -> JsResult<Node<Vec<Node<Statement>>>> {
+        let start = self.position();
+        try!(self.expect(TokenType::LeftBrace));
+        self.parse_directives();
+        let body = try!(self.allow_in(|p| p.parse_stmt_list()));
+        try!(self.expect(TokenType::RightBrace));
+        Ok(self.finish(start, body))
+    }
+
+    pub fn parse_formals_list(&mut self) -> JsResult<Vec<Node<Pattern>>> {
+        let mut params = Vec::new();
+        loop {
+            match self.peek() {
+                Some(TokenType::RightParen) => break,
+                Some(TokenType::ThreePoints) => {
+                    let start = self.position();
+                    try!(self.bump());
+                    let rest = try!(self.parse_binding_target());
+                    params.push(self.finish(start, Pattern::Rest(Box::new(rest))));
+                    break
+                }
+                _ => ()
+            }
+            params.push(try!(self.parse_binding_element()));
+            if !try!(self.consume(TokenType::Comma)) {
+                break
+            }
+        }
+        Ok(params)
     }
 
+    pub fn parse_formal_parameters(&mut self) -> JsResult<Vec<Node<Pattern>>> {
+        try!(self.expect(TokenType::LeftParen));
+        let params = try!(self.parse_formals_list());
+        try!(self.expect(TokenType::RightParen));
+        Ok(params)
+    }
 
-    pub fn parse_function_expr(&mut self) -> JsResult<Item> {
+    pub fn parse_function_expr(&mut self) -> JsResult<Option<Node<Expression>>> {
         println!("parse_function_expr {:?}", self.peek());
+        let start = self.position();
+        let is_async = self.at_async_function();
+        if is_async {
+            try!(self.bump());
+        }
         if !try!(self.consume(TokenType::Function)) {
-            return Ok(Item::None)
+            return Ok(None)
         }
-        try!(self.consume(TokenType::Multiple));
-        try!(self.consume_identifier());
-        try!(self.parse_formal_parameters());
-        self.parse_block()
+        let generator = try!(self.consume(TokenType::Multiple));
+        let outer = (self.generator, self.is_async);
+        // the name of a generator or async expression is bound inside it
+        self.generator = generator;
+        self.is_async = is_async;
+        let id = self.consume_identifier();
+        self.generator = outer.0;
+        self.is_async = outer.1;
+        let id = try!(id);
+        let (params, body) = try!(self.parse_function_rest(generator, is_async, false, false));
+        Ok(Some(self.finish(start, Expression::Function(Function {
+            id: id,
+            params: params,
+            body: body,
+            generator: generator,
+            is_async: is_async
+        }))))
     }
-}
\ No newline at end of file
+
+    pub fn parse_arrow_function(&mut self, start: Position, params: Vec<Node<Pattern>>, is_async: bool) -> JsResult<Node<Expression>> {
+        try!(self.no_newline_before());
+        try!(self.expect(TokenType::Lamda));
+        try!(self.check_parameters(&params, true));
+        let outer_parameters = self.start_parameters();
+        let body = if self.peek() == Some(TokenType::LeftBrace) {
+            let outer = (self.generator, self.is_async, self.strict, self.in_function);
+            self.generator = false;
+            self.is_async = is_async;
+            self.in_function = true;
+            let body = self.parse_function_body();
+            self.generator = outer.0;
+            self.is_async = outer.1;
+            self.strict = outer.2;
+            self.in_function = outer.3;
+            ArrowBody::Block(try!(body))
+        } else {
+            let outer = self.is_async;
+            self.is_async = is_async;
+            let body = self.expect_assign_expr();
+            self.is_async = outer;
+            ArrowBody::Expression(Box::new(try!(body)))
+        };
+        self.yield_await = outer_parameters;
+        Ok(self.finish(start, Expression::Arrow {
+            params: params,
+            body: body,
+            is_async: is_async
+        }))
+    }
+}
@@ -0,0 +1,131 @@
+use ast::node::{Node, Statement, Expression, ForInit, ForTarget, VariableKind};
+use error::JsResult;
+use error::error::SyntaxErrorType;
+use lexer::enums::{TokenType, Contextual};
+use scope::parser::{Parser};
+
+impl<'a> Parser<'a> {
+    pub fn parse_for(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
+        try!(self.bump());
+        // `for await` needs `await` to be a keyword, in async functions and modules
+        let is_await = (self.is_async || self.module) && try!(self.consume(TokenType::Contextual(Contextual::Await)));
+        try!(self.expect(TokenType::LeftParen));
+
+        let outer = self.no_in;
+        self.no_in = true;
+        // the head may still become the target of a `for-in` or `for-of`
+        let cover = self.start_cover();
+        let init = try!(self.parse_for_init(is_await));
+        self.no_in = outer;
+
+        match self.peek() {
+            Some(TokenType::In) if !is_await => {
+                let left = try!(self.for_target(init));
+                try!(self.end_cover(cover));
+                try!(self.bump());
+                let right = try!(self.parse_expr());
+                try!(self.expect(TokenType::RightParen));
+                let body = try!(self.expect_stmt());
+                Ok(self.finish(start, Statement::ForIn {
+                    left: left,
+                    right: right,
+                    body: Box::new(body)
+                }))
+            }
+            Some(TokenType::Contextual(Contextual::Of)) => {
+                let left = try!(self.for_target(init));
+                try!(self.end_cover(cover));
+                try!(self.bump());
+                let right = try!(self.expect_assign_expr());
+                try!(self.expect(TokenType::RightParen));
+                let body = try!(self.expect_stmt());
+                Ok(self.finish(start, Statement::ForOf {
+                    left: left,
+                    right: right,
+                    body: Box::new(body),
+                    is_await: is_await
+                }))
+            }
+            _ if is_await => self.expected(&["`of`"]),
+            _ => {
+                try!(self.end_cover(cover));
+                if let Some(ForInit::Variable(ref declaration)) = init {
+                    try!(self.check_initializers(declaration));
+                }
+                try!(self.expect(TokenType::Semicolon));
+                let test = if self.peek_is(&TokenType::Semicolon) {
+                    None
+                } else {
+                    Some(try!(self.parse_expr()))
+                };
+                try!(self.expect(TokenType::Semicolon));
+                let update = if self.peek_is(&TokenType::RightParen) {
+                    None
+                } else {
+                    Some(try!(self.parse_expr()))
+                };
+                try!(self.expect(TokenType::RightParen));
+                let body = try!(self.expect_stmt());
+                Ok(self.finish(start, Statement::For {
+                    init: init,
+                    test: test,
+                    update: update,
+                    body: Box::new(body)
+                }))
+            }
+        }
+    }
+
+    /// The declaration or expression before the first `;`, `in` or `of` of a `for` head.
+    fn parse_for_init(&mut self, is_await: bool) -> JsResult<Option<ForInit>> {
+        let kind = match self.peek() {
+            Some(TokenType::Semicolon) => return Ok(None),
+            Some(TokenType::Var) => VariableKind::Var,
+            Some(TokenType::Const) => VariableKind::Const,
+            _ if self.at_let_declaration() => VariableKind::Let,
+            // the target of a `for-of` can not start with `let`, nor with `async of` unless it
+            // is a `for await`
+            token => {
+                let span = self.next_span();
+                let restricted = match token {
+                    Some(TokenType::Contextual(Contextual::Let)) => true,
+                    Some(TokenType::Contextual(Contextual::Async)) => {
+                        !is_await && self.peek_at(1) == Some(TokenType::Contextual(Contextual::Of))
+                    }
+                    _ => false
+                };
+                let first = try!(self.expect_assign_expr_or_pattern());
+                let expr = try!(self.parse_sequence(span.start, first));
+                if restricted && self.peek() == Some(TokenType::Contextual(Contextual::Of)) {
+                    let token = token.unwrap().into_owned();
+                    return self.error_at(SyntaxErrorType::Unexpected(token), span, &[])
+                }
+                return Ok(Some(ForInit::Expression(expr)))
+            }
+        };
+        let declaration = try!(self.parse_variable_declaration_list(kind));
+        Ok(Some(ForInit::Variable(declaration)))
+    }
+
+    /// Turns the head of a `for-in` or `for-of` into its target, a single declarator without
+    /// initializer or an assignment pattern.
+    fn for_target(&mut self, init: Option<ForInit>) -> JsResult<ForTarget> {
+        match init {
+            Some(ForInit::Variable(declaration)) => {
+                let single = declaration.item.declarations.len() == 1 && declaration.item.declarations[0].item.init.is_none();
+                if !single {
+                    return self.unexpected()
+                }
+                Ok(ForTarget::Variable(declaration))
+            }
+            Some(ForInit::Expression(expr)) => {
+                if let Expression::Assignment { .. } = expr.item {
+                    return self.error_at(SyntaxErrorType::InvalidAssignmentTarget, expr.span, &[])
+                }
+                self.assignment_target(expr).map(ForTarget::Pattern)
+            }
+            None => self.unexpected()
+        }
+    }
+}
@@ -1,181 +1,261 @@
+use ast::node::{Node, Expression};
 use error::JsResult;
-//use error::error::{Error, ErrorType, SyntaxErrorType};
-use lexer::enums::{TokenType};
-use scope::parser::{Parser, Item};
+use error::error::SyntaxErrorType;
+use lexer::enums::{TokenType, Contextual};
+use scope::parser::{Parser};
 
 macro_rules! wait {
     ($expr:expr) => (match $expr {
-        Item::None => (),
-        Item::Item => return Ok(Item::Item),
+        None => (),
+        Some(item) => return Ok(Some(item)),
     })
 }
 
 macro_rules! none {
     ($expr:expr) => (match $expr {
-        Item::None => return Ok(Item::None),
-        Item::Item => (),
+        None => return Ok(None),
+        Some(item) => item,
     })
 }
 
-impl Parser {
-    pub fn parse_left_hand_side_expr(&mut self) -> JsResult<Item> {//done
+impl<'a> Parser<'a> {
+    pub fn parse_left_hand_side_expr(&mut self) -> JsResult<Option<Node<Expression>>> {//done
         println!("parse_left_hand_side_expr {:?}", self.peek());
-        wait!(try!(self.parse_call_expr()));
-        self.parse_new_expr()
+        self.parse_call_expr()
     }
 
-    pub fn parse_new_expr(&mut self) -> JsResult<Item> {//done
+    pub fn parse_new_expr(&mut self) -> JsResult<Option<Node<Expression>>> {//done
         println!("parse_new_expr {:?}", self.peek());
-        wait!(try!(self.parse_member_expr()));
-        if try!(self.consume(TokenType::New)) {
-            try!(self.parse_new_expr());
-            Ok(Item::Item)
-        } else {
-            Ok(Item::None)
+        if self.peek() != Some(TokenType::New) {
+            return Ok(None)
         }
+        if self.peek_at(1) == Some(TokenType::Point) {
+            return self.parse_meta_prop().map(Some)
+        }
+        let start = self.position();
+        try!(self.bump());
+        let callee = match try!(self.parse_member_expr()) {
+            Some(callee) => callee,
+            None => return self.expected(&["expression"])
+        };
+        let arguments = try!(self.parse_arguments()).unwrap_or(Vec::new());
+        Ok(Some(self.finish(start, Expression::New {
+            callee: Box::new(callee),
+            arguments: arguments
+        })))
+    }
+
+    pub fn parse_super(&mut self) -> JsResult<Node<Expression>> {
+        let start = self.position();
+        try!(self.expect(TokenType::Super));
+        Ok(self.finish(start, Expression::Super))
     }
 
-    pub fn parse_super_prop(&mut self) -> JsResult<Item> {
-        if !try!(self.consume(TokenType::Super)) {
-            return Ok(Item::None)
+    pub fn parse_super_prop(&mut self) -> JsResult<Option<Node<Expression>>> {
+        if self.peek() != Some(TokenType::Super) {
+            return Ok(None)
         }
-        if try!(self.consume(TokenType::LeftBracket)) {
-            try!(self.expect(TokenType::RightBracket));
-        } else {
-            try!(self.expect(TokenType::Point));
-            try!(self.expect_identifier());
+        match self.peek_at(1) {
+            Some(TokenType::LeftBracket) | Some(TokenType::Point) => (),
+            _ => return Ok(None)
+        }
+        if !self.super_property {
+            return self.fatal(SyntaxErrorType::InvalidSuper)
         }
-        Ok(Item::Item)
+        let object = try!(self.parse_super());
+        let (property, computed) = none!(try!(self.parse_member()));
+        Ok(Some(self.finish(object.span.start, Expression::Member {
+            object: Box::new(object),
+            property: Box::new(property),
+            computed: computed
+        })))
     }
 
-    pub fn parse_meta_prop(&mut self) -> JsResult<Item> {
+    pub fn parse_meta_prop(&mut self) -> JsResult<Node<Expression>> {
+        if !self.new_target {
+            return self.fatal(SyntaxErrorType::InvalidNewTarget)
+        }
+        let start = self.position();
         try!(self.expect(TokenType::New));
+        let meta = self.finish(start, String::from("new"));
         try!(self.expect(TokenType::Point));
-        try!(self.expect(TokenType::Target));
-        Ok(Item::Item)
+        let property_start = self.position();
+        try!(self.expect(TokenType::Contextual(Contextual::Target)));
+        let property = self.finish(property_start, String::from("target"));
+        Ok(self.finish(start, Expression::MetaProperty {
+            meta: meta,
+            property: property
+        }))
     }
 
-    pub fn bump_new_and_member_expr_and_arguments(&mut self) -> JsResult<Item> {
-        println!("bump_new_and_member_expr_and_arguments {:?}", self.peek());
-        if !try!(self.consume(TokenType::New)) {
-            return Ok(Item::None)
-        }
-        try!(self.parse_member_expr());
-        try!(self.parse_arguments());
-        Ok(Item::Item)
+    pub fn parse_arguments(&mut self) -> JsResult<Option<Vec<Node<Expression>>>> {
+        self.parse_argument_list(false)
     }
 
-    pub fn parse_arguments(&mut self) -> JsResult<Item> {
+    /// With `cover` the cover errors of the arguments are left to the caller, for the arguments
+    /// of `async(...)` that may be the parameters of an arrow function.
+    pub fn parse_argument_list(&mut self, cover: bool) -> JsResult<Option<Vec<Node<Expression>>>> {
         println!("parse_arguments {:?}", self.peek());
-        if !try!(self.consume(TokenType::LeftParen)) {
-            return Ok(Item::None)
+        if !try!(self.consume_continuation(TokenType::LeftParen)) {
+            return Ok(None)
         }
-        if !try!(self.consume(TokenType::ThreePoints)) {
-            try!(self.parse_expr());
-            try!(self.consume(TokenType::ThreePoints));
+        let mut arguments = Vec::new();
+        loop {
+            if try!(self.consume(TokenType::RightParen)) {
+                break
+            }
+            let outer = self.start_cover();
+            let argument = if self.peek() == Some(TokenType::ThreePoints) {
+                try!(self.allow_in(|p| p.parse_spread_element()))
+            } else {
+                try!(self.allow_in(|p| p.expect_assign_expr_or_pattern()))
+            };
+            if cover {
+                self.merge_cover(outer);
+            } else {
+                try!(self.end_cover(outer));
+            }
+            arguments.push(argument);
+            if !try!(self.consume(TokenType::Comma)) {
+                try!(self.expect(TokenType::RightParen));
+                break
+            }
         }
-        try!(self.consume_all_lineterminates());
-        try!(self.expect(TokenType::RightParen));
-        Ok(Item::Item)
+        Ok(Some(arguments))
     }
 
-    pub fn parse_member(&mut self) -> JsResult<Item> {
+    pub fn parse_member(&mut self) -> JsResult<Option<(Node<Expression>, bool)>> {
         println!("parse_member {:?}", self.peek());
-        if try!(self.consume(TokenType::LeftBracket)) {
-            try!(self.parse_expr());
+        if try!(self.consume_continuation(TokenType::LeftBracket)) {
+            let property = try!(self.allow_in(|p| p.parse_expr()));
             try!(self.expect(TokenType::RightBracket));
-            return Ok(Item::Item)
+            return Ok(Some((property, true)))
         }
-        if try!(self.consume(TokenType::Point)) {
-            try!(self.expect_identifier_name());
-            return Ok(Item::Item)
+        if try!(self.consume_continuation(TokenType::Point)) {
+            if let Some(TokenType::PrivateName(name)) = self.peek() {
+                let span = self.next_span();
+                try!(self.bump());
+                let name = Node::new(span, name.into_owned());
+                try!(self.use_private_name(name.clone()));
+                return Ok(Some((Node::new(span, Expression::PrivateName(name.item)), false)))
+            }
+            let name = try!(self.expect_identifier_name());
+            return Ok(Some((Node::new(name.span, Expression::Identifier(name.item)), false)))
         }
-        Ok(Item::None)
-    }
-
-    pub fn member_expr_and_members(&mut self) -> JsResult<Item> {
-        println!("member_expr_and_members {:?}", self.peek());
-        none!(try!(self.parse_member_expr()));
-        self.parse_member()
+        Ok(None)
     }
 
-
-    pub fn parse_member_expr(&mut self) -> JsResult<Item> {
-        let mut first = false;
-        loop {
-            println!("parse_member_expr {:?} {:?}", self.peek(), first);
-            match try!(self.parse_primary_expr()) {
-                Item::Item => {
-                    first = true;
-                    continue
-                },
-                Item::None => (),
+    pub fn parse_member_expr(&mut self) -> JsResult<Option<Node<Expression>>> {
+        let start = self.position();
+        let mut object = match try!(self.parse_new_expr()) {
+            Some(expr) => expr,
+            None => match try!(self.parse_primary_expr()) {
+                Some(expr @ Node { item: Expression::Arrow { .. }, .. }) => return Ok(Some(expr)),
+                Some(expr) => expr,
+                None => none!(try!(self.parse_super_prop()))
             }
-            match try!(self.parse_super_prop()) {
-                Item::Item => {
-                    first = true;
-                    continue
-                },
-                Item::None => (),
-            }
-            if !first {
-                return Ok(Item::None)
+        };
+        loop {
+            if self.at_template() {
+                self.forget_parenthesized(start);
+                object = try!(self.parse_tagged_template(start, object));
+                continue
             }
             match try!(self.parse_member()) {
-                Item::Item => continue,
-                Item::None => (),
+                Some((property, computed)) => {
+                    self.forget_parenthesized(start);
+                    object = self.finish(start, Expression::Member {
+                        object: Box::new(object),
+                        property: Box::new(property),
+                        computed: computed
+                    });
+                },
+                None => break,
             }
-            break
         }
-        Ok(Item::Item)
+        Ok(Some(object))
     }
 
-    pub fn parse_super_call(&mut self) -> JsResult<Item> {
+    pub fn parse_super_call(&mut self) -> JsResult<Option<Node<Expression>>> {
         println!("parse_super_call {:?}", self.peek());
-        if !try!(self.consume(TokenType::Super)) {
-            return Ok(Item::None)
+        if self.peek() != Some(TokenType::Super) || self.peek_at(1) != Some(TokenType::LeftParen) {
+            return Ok(None)
+        }
+        if !self.super_call {
+            return self.fatal(SyntaxErrorType::InvalidSuper)
         }
-        try!(self.parse_arguments());
-        Ok(Item::Item)
+        let callee = try!(self.parse_super());
+        let arguments = none!(try!(self.parse_arguments()));
+        Ok(Some(self.finish(callee.span.start, Expression::Call {
+            callee: Box::new(callee),
+            arguments: arguments
+        })))
     }
 
-    pub fn member_and_arguments(&mut self) -> JsResult<Item> {
-        println!("member_and_arguments {:?}", self.peek());
-        none!(try!(self.parse_member_expr()));
-        self.parse_arguments()
+    pub fn member_or_super_call(&mut self) -> JsResult<Option<Node<Expression>>> {
+        wait!(try!(self.parse_super_call()));
+        self.parse_member_expr()
     }
 
-    pub fn parse_call_expr(&mut self) -> JsResult<Item> {
-        let mut first = false;
+    pub fn parse_call_expr(&mut self) -> JsResult<Option<Node<Expression>>> {
+        let start = self.position();
+        let mut callee = none!(try!(self.member_or_super_call()));
+        if let Expression::Arrow { .. } = callee.item {
+            return Ok(Some(callee))
+        }
+        // `async (a, b) => a` starts like a call of a function named `async`
+        let async_callee = match callee.item {
+            Expression::Identifier(ref name) => name == "async" && callee.span.end == self.end(),
+            _ => false
+        };
+        if async_callee && self.peek() == Some(TokenType::LeftParen) && !self.newline_before() {
+            let outer = self.start_cover();
+            let outer_parameters = self.start_parameters();
+            let arguments = none!(try!(self.parse_argument_list(true)));
+            if self.peek() == Some(TokenType::Lamda) {
+                let params = try!(self.arguments_to_params(arguments));
+                try!(self.end_cover(outer));
+                try!(self.end_parameters(outer_parameters));
+                return self.parse_arrow_function(start, params, true).map(Some)
+            }
+            try!(self.end_cover(outer));
+            self.merge_parameters(outer_parameters);
+            callee = self.finish(start, Expression::Call {
+                callee: Box::new(callee),
+                arguments: arguments
+            });
+        }
         loop {
-            println!("parse_call_expr {:?} {:?}", self.peek(), first);
-            match try!(self.member_and_arguments()) {
-                Item::Item => {
-                    first = true;
+            match try!(self.parse_arguments()) {
+                Some(arguments) => {
+                    self.forget_parenthesized(start);
+                    callee = self.finish(start, Expression::Call {
+                        callee: Box::new(callee),
+                        arguments: arguments
+                    });
                     continue
                 },
-                Item::None => (),
+                None => (),
             }
-            match try!(self.parse_super_call()) {
-                Item::Item => {
-                    first = true;
+            match try!(self.parse_member()) {
+                Some((property, computed)) => {
+                    self.forget_parenthesized(start);
+                    callee = self.finish(start, Expression::Member {
+                        object: Box::new(callee),
+                        property: Box::new(property),
+                        computed: computed
+                    });
                     continue
                 },
-                Item::None => (),
-            }
-            if !first {
-                return Ok(Item::None)
+                None => (),
             }
-            match try!(self.parse_member()) {
-                Item::Item => continue,
-                Item::None => (),
-            }
-            match try!(self.parse_arguments()) {
-                Item::Item => continue,
-                Item::None => (),
+            if self.at_template() {
+                self.forget_parenthesized(start);
+                callee = try!(self.parse_tagged_template(start, callee));
+                continue
             }
             break
         }
-        Ok(Item::Item)
+        Ok(Some(callee))
     }
-}
\ No newline at end of file
+}
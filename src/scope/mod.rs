@@ -3,8 +3,12 @@ pub mod parser;
 mod expr;
 mod try;
 mod switch;
+mod iteration;
 mod block;
 mod lefthandsideexpr;
 mod function;
+mod class;
 mod object;
-mod operators;
\ No newline at end of file
+mod operators;
+mod pattern;
+mod template;
\ No newline at end of file
@@ -1,95 +1,212 @@
+use ast::node::{Node, Expression, Function, Property, PropertyKey, PropertyKind, Pattern, AssignmentOperator};
 use error::JsResult;
-use lexer::enums::{TokenType};
-use scope::parser::{Parser, Item};
+use error::error::SyntaxErrorType;
+use lexer::enums::{TokenType, LiteralType, Contextual};
+use lexer::token::Position;
+use scope::expr::identifier;
+use scope::parser::{Parser};
 
-macro_rules! wait {
-    ($expr:expr) => (match $expr {
-        Item::Item => return Ok(Item::Item),
-        Item::None => (),
-    })
-}
-
-
-macro_rules! none {
-    ($expr:expr) => (match $expr {
-        Item::None => return Ok(Item::None),
-        Item::Item => (),
-    })
-}
-
-impl Parser {
-    pub fn parse_object_literal(&mut self) -> JsResult<Item> {
+impl<'a> Parser<'a> {
+    pub fn parse_object_literal(&mut self) -> JsResult<Option<Node<Expression>>> {
         println!("parse_object_literal {:?}", self.peek());
+        let start = self.position();
         try!(self.expect(TokenType::LeftBrace));
+        let mut properties = Vec::new();
         loop {
-            try!(self.consume_all_lineterminates());
             match try!(self.parse_property_definition()) {
-                Item::None => {
+                None => {
                     println!("empty");
                     break
                 },
-                Item::Item => ()
+                Some(property) => properties.push(property)
             }
             if !try!(self.consume(TokenType::Comma)) {
                 break
             }
         }
-        try!(self.consume_all_lineterminates());
         try!(self.expect(TokenType::RightBrace));
-        Ok(Item::Item)
+        Ok(Some(self.finish(start, Expression::Object(properties))))
     }
 
-    pub fn parse_property_definition(&mut self) -> JsResult<Item> {
-        println!("parse_property_definition {:?}", self.peek());
-        match self.peek() {
-            Some(TokenType::Get) => {
+    pub fn parse_property_name(&mut self) -> JsResult<Node<PropertyKey>> {
+        let start = self.position();
+        let key = match self.peek() {
+            Some(TokenType::Literal(LiteralType::String(s, raw))) => {
                 try!(self.bump());
-                if try!(self.consume(TokenType::Colon)) {
-                    self.parse_assign_expr()
-                } else {
-                    try!(self.expect_identifier());
-                    try!(self.expect(TokenType::LeftParen));
-                    try!(self.expect(TokenType::RightParen));
-                    self.parse_block()
-                }
+                PropertyKey::Literal(LiteralType::String(s, raw).into_owned())
             }
-            Some(TokenType::Set) => {
+            Some(TokenType::Literal(LiteralType::Integer(i))) => {
                 try!(self.bump());
-                if try!(self.consume(TokenType::Colon)) {
-                    self.parse_assign_expr()
-                } else {
-                    try!(self.expect_identifier());
-                    try!(self.expect(TokenType::LeftParen));
-                    try!(self.expect_identifier());
-                    try!(self.expect(TokenType::RightParen));
-                    self.parse_block()
-                }
+                PropertyKey::Literal(LiteralType::Integer(i))
             }
-            Some(TokenType::LeftBracket) => Ok(Item::None),
-            Some(TokenType::Multiple) => {
+            Some(TokenType::Literal(LiteralType::Float(f))) => {
                 try!(self.bump());
-                try!(self.expect_identifier());
-                try!(self.expect(TokenType::LeftParen));
-                try!(self.parse_formal_parameters());
-                try!(self.expect(TokenType::RightParen));
-                self.parse_block()
-            },
-            Some(TokenType::Identifier(_)) => {
+                PropertyKey::Literal(LiteralType::Float(f))
+            }
+            Some(TokenType::Literal(LiteralType::BigInt(digits))) => {
+                try!(self.bump());
+                PropertyKey::Literal(LiteralType::BigInt(digits).into_owned())
+            }
+            Some(TokenType::LeftBracket) => {
                 try!(self.bump());
-                if try!(self.consume(TokenType::LeftParen)) {
-                    try!(self.parse_formal_parameters());
-                    try!(self.expect(TokenType::RightParen));
-                    self.parse_block()
+                let key = try!(self.expect_assign_expr());
+                try!(self.expect(TokenType::RightBracket));
+                PropertyKey::Computed(key)
+            }
+            _ => {
+                let name = try!(self.expect_identifier_name());
+                PropertyKey::Identifier(name.item)
+            }
+        };
+        Ok(self.finish(start, key))
+    }
+
+    pub fn parse_method(&mut self, generator: bool, is_async: bool, super_call: bool) -> JsResult<Node<Expression>> {
+        let start = self.position();
+        let (params, body) = try!(self.parse_function_rest(generator, is_async, true, super_call));
+        Ok(self.finish(start, Expression::Function(Function {
+            id: None,
+            params: params,
+            body: body,
+            generator: generator,
+            is_async: is_async
+        })))
+    }
+
+    /// The function of a getter, which has no parameters, or of a setter, which has exactly one
+    /// that is not a rest element.
+    pub fn parse_accessor_method(&mut self, setter: bool) -> JsResult<Node<Expression>> {
+        let value = try!(self.parse_method(false, false, false));
+        let valid = match value.item {
+            Expression::Function(Function { ref params, .. }) => match params[..] {
+                [] => !setter,
+                [Node { item: Pattern::Rest(_), .. }] => false,
+                [_] => setter,
+                _ => false
+            },
+            _ => true
+        };
+        if !valid {
+            return self.error_at(SyntaxErrorType::InvalidAccessor, value.span, &[])
+        }
+        Ok(value)
+    }
+
+    pub fn parse_accessor(&mut self, start: Position, kind: PropertyKind) -> JsResult<Node<Property>> {
+        try!(self.bump());
+        let key = try!(self.parse_property_name());
+        let value = try!(self.parse_accessor_method(kind == PropertyKind::Set));
+        Ok(self.finish(start, Property {
+            key: key,
+            value: value,
+            kind: kind,
+            method: false,
+            shorthand: false
+        }))
+    }
+
+    pub fn parse_property_definition(&mut self) -> JsResult<Option<Node<Property>>> {
+        let start = self.position();
+        match self.peek() {
+            Some(TokenType::Contextual(Contextual::Get)) | Some(TokenType::Contextual(Contextual::Set)) => {
+                let kind = if self.peek() == Some(TokenType::Contextual(Contextual::Get)) {
+                    PropertyKind::Get
                 } else {
-                    if try!(self.consume(TokenType::Colon)) {
-                        return self.parse_assign_expr()
-                    } else {
-                        Ok(Item::None)
+                    PropertyKind::Set
+                };
+                match self.peek_at(1) {
+                    Some(TokenType::Colon) |
+                    Some(TokenType::LeftParen) |
+                    Some(TokenType::Comma) |
+                    Some(TokenType::RightBrace) => (),
+                    _ => return self.parse_accessor(start, kind).map(Some)
+                }
+            }
+            Some(TokenType::Contextual(Contextual::Async)) if !self.newline_before_at(1) => {
+                match self.peek_at(1) {
+                    Some(TokenType::Colon) |
+                    Some(TokenType::LeftParen) |
+                    Some(TokenType::Comma) |
+                    Some(TokenType::Equal) |
+                    Some(TokenType::RightBrace) => (),
+                    _ => {
+                        try!(self.bump());
+                        let generator = try!(self.consume(TokenType::Multiple));
+                        let key = try!(self.parse_property_name());
+                        let value = try!(self.parse_method(generator, true, false));
+                        return Ok(Some(self.finish(start, Property {
+                            key: key,
+                            value: value,
+                            kind: PropertyKind::Init,
+                            method: true,
+                            shorthand: false
+                        })))
                     }
                 }
+            }
+            Some(TokenType::Multiple) => {
+                try!(self.bump());
+                let key = try!(self.parse_property_name());
+                let value = try!(self.parse_method(true, false, false));
+                return Ok(Some(self.finish(start, Property {
+                    key: key,
+                    value: value,
+                    kind: PropertyKind::Init,
+                    method: true,
+                    shorthand: false
+                })))
             },
-            Some(_) => Ok(Item::None),
-            None => Ok(Item::None),
+            Some(TokenType::RightBrace) |
+            None => return Ok(None),
+            Some(_) => ()
         }
+
+        let shorthand = self.peek().as_ref().and_then(identifier);
+        let key = try!(self.parse_property_name());
+        if self.peek() == Some(TokenType::LeftParen) {
+            let value = try!(self.parse_method(false, false, false));
+            return Ok(Some(self.finish(start, Property {
+                key: key,
+                value: value,
+                kind: PropertyKind::Init,
+                method: true,
+                shorthand: false
+            })))
+        }
+        if try!(self.consume(TokenType::Colon)) {
+            let value = try!(self.expect_assign_expr_or_pattern());
+            return Ok(Some(self.finish(start, Property {
+                key: key,
+                value: value,
+                kind: PropertyKind::Init,
+                method: false,
+                shorthand: false
+            })))
+        }
+        let name = match shorthand {
+            Some(name) => name,
+            None => return self.expected(&["`:`", "`(`"])
+        };
+        try!(self.check_identifier(&name, key.span));
+        // `{ a = 1 }` is only valid as a destructuring target, the initializer is kept
+        // so the object can still be turned into a pattern and the `=` is an error otherwise
+        if self.peek() == Some(TokenType::Equal) && self.cover.shorthand_init.is_none() {
+            self.cover.shorthand_init = Some(self.next_span());
+        }
+        let value = match try!(self.parse_initializer()) {
+            Some(init) => self.finish(start, Expression::Assignment {
+                operator: AssignmentOperator::Equal,
+                left: Box::new(Node::new(key.span, Pattern::Identifier(name))),
+                right: Box::new(init)
+            }),
+            None => Node::new(key.span, Expression::Identifier(name))
+        };
+        Ok(Some(self.finish(start, Property {
+            key: key,
+            value: value,
+            kind: PropertyKind::Init,
+            method: false,
+            shorthand: true
+        })))
     }
-}
\ No newline at end of file
+}
@@ -1,61 +1,115 @@
+use ast::node::{Node, Expression, BinaryOperator, LogicalOperator};
 use error::JsResult;
-//use error::error::{Error, ErrorType, SyntaxErrorType};
+use error::error::SyntaxErrorType;
 use lexer::enums::{TokenType};
-use scope::parser::{Parser, Item};
+use lexer::token::Position;
+use scope::parser::{Parser};
 
-macro_rules! wait {
+macro_rules! none {
     ($expr:expr) => (match $expr {
-        Item::None => (),
-        Item::Item => return Ok(Item::Item),
+        None => return Ok(None),
+        Some(item) => item,
     })
 }
 
-macro_rules! none {
-    ($expr:expr) => (match $expr {
-        Item::None => return Ok(Item::None),
-        Item::Item => (),
-    })
+enum Operator {
+    Binary(BinaryOperator),
+    Logical(LogicalOperator)
 }
 
-impl Parser {
-    pub fn dump_and_parse_logical_expr(&mut self) -> JsResult<Item> {
-        println!("dump_and_parse_logical_expr {:?}", self.peek());
-        try!(self.bump());
-        self.parse_logical_expr(Item::None)
-    }
+fn binary_operator(token: &TokenType) -> Option<(Operator, u32)> {
+    let operator = match *token {
+        TokenType::Or => (Operator::Logical(LogicalOperator::Or), 1),
+        TokenType::And => (Operator::Logical(LogicalOperator::And), 2),
+        TokenType::OrBitwise => (Operator::Binary(BinaryOperator::OrBitwise), 3),
+        TokenType::Xor => (Operator::Binary(BinaryOperator::Xor), 4),
+        TokenType::AndBitwise => (Operator::Binary(BinaryOperator::AndBitwise), 5),
+        TokenType::IsEqual => (Operator::Binary(BinaryOperator::IsEqual), 6),
+        TokenType::IsNotEqual => (Operator::Binary(BinaryOperator::IsNotEqual), 6),
+        TokenType::IsSame => (Operator::Binary(BinaryOperator::IsSame), 6),
+        TokenType::IsNotSame => (Operator::Binary(BinaryOperator::IsNotSame), 6),
+        TokenType::SmallThan => (Operator::Binary(BinaryOperator::SmallThan), 7),
+        TokenType::GreaterThan => (Operator::Binary(BinaryOperator::GreaterThan), 7),
+        TokenType::SmallAndEqualThan => (Operator::Binary(BinaryOperator::SmallAndEqualThan), 7),
+        TokenType::GreaterAndEqualThan => (Operator::Binary(BinaryOperator::GreaterAndEqualThan), 7),
+        TokenType::Instanceof => (Operator::Binary(BinaryOperator::Instanceof), 7),
+        TokenType::In => (Operator::Binary(BinaryOperator::In), 7),
+        TokenType::LeftShift => (Operator::Binary(BinaryOperator::LeftShift), 8),
+        TokenType::RightShift => (Operator::Binary(BinaryOperator::RightShift), 8),
+        TokenType::RightShiftUnsigned => (Operator::Binary(BinaryOperator::RightShiftUnsigned), 8),
+        TokenType::Plus => (Operator::Binary(BinaryOperator::Plus), 9),
+        TokenType::Minus => (Operator::Binary(BinaryOperator::Minus), 9),
+        TokenType::Multiple => (Operator::Binary(BinaryOperator::Multiple), 10),
+        TokenType::Divide => (Operator::Binary(BinaryOperator::Divide), 10),
+        TokenType::Mod => (Operator::Binary(BinaryOperator::Mod), 10),
+        TokenType::Exp => (Operator::Binary(BinaryOperator::Exp), 11),
+        _ => return None
+    };
+    Some(operator)
+}
 
-    pub fn parse_logical_expr(&mut self, first: Item) -> JsResult<Item> {
+impl<'a> Parser<'a> {
+    pub fn parse_logical_expr(&mut self, first: Option<(Position, Node<Expression>)>) -> JsResult<Option<Node<Expression>>> {
         println!("parse_logical_expr {:?} {:?}", self.peek(), first);
-        none!(try!(self.parse_unary_expr(first)));
+        let start = match first {
+            Some((start, _)) => start,
+            None => self.position()
+        };
+        let left = none!(try!(self.parse_unary_expr(first)));
         println!("  parse_logical_expr {:?}", self.peek());
+        self.parse_binary_expr(start, left, 0).map(Some)
+    }
+
+    /// Parses the operators after `left`, which starts at `start` with any parentheses around it.
+    pub fn parse_binary_expr(&mut self, start: Position, left: Node<Expression>, min: u32) -> JsResult<Node<Expression>> {
+        let mut left = left;
         loop {
-            match self.peek() {
-                Some(TokenType::Plus) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Minus) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Multiple) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Divide) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Mod) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Exp) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::LeftShift) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::RightShift) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::RightShiftUnsigned) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::GreaterThan) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::GreaterAndEqualThan) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::SmallAndEqualThan) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::SmallThan) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Instanceof) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::In) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::IsEqual) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::IsNotEqual) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::IsSame) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::IsNotSame) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::And) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::AndBitwise) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Or) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::OrBitwise) => self.dump_and_parse_logical_expr(),
-                Some(TokenType::Xor) => self.dump_and_parse_logical_expr(),
-                _ => return Ok(Item::Item)
+            let (operator, precedence) = match self.peek().as_ref().and_then(binary_operator) {
+                Some((Operator::Binary(BinaryOperator::In), _)) if self.no_in => break,
+                Some((operator, precedence)) => {
+                    if precedence <= min {
+                        break
+                    }
+                    (operator, precedence)
+                }
+                None => break
+            };
+            // `-a ** b` is ambiguous, the unary operand has to be parenthesized
+            if let Operator::Binary(BinaryOperator::Exp) = operator {
+                let unary = match left.item {
+                    Expression::Unary { .. } | Expression::Await(_) => left.span.end == self.end(),
+                    _ => false
+                };
+                if unary {
+                    return self.unexpected()
+                }
             }
+            try!(self.bump());
+            let right_start = self.position();
+            let right = try!(self.expect_unary_expr());
+            // ** is right associative, every other binary operator binds to the left
+            let right = match operator {
+                Operator::Binary(BinaryOperator::Exp) => try!(self.parse_binary_expr(right_start, right, precedence - 1)),
+                _ => try!(self.parse_binary_expr(right_start, right, precedence))
+            };
+            left = match operator {
+                Operator::Binary(operator) => self.finish(start, Expression::Binary {
+                    operator: operator,
+                    left: Box::new(left),
+                    right: Box::new(right)
+                }),
+                Operator::Logical(operator) => self.finish(start, Expression::Logical {
+                    operator: operator,
+                    left: Box::new(left),
+                    right: Box::new(right)
+                })
+            };
         }
+        // a `#name` has to be the left side of the `in` that follows it
+        if let Expression::PrivateName(ref name) = left.item {
+            let span = left.span;
+            return self.error_at(SyntaxErrorType::Unexpected(TokenType::PrivateName(name.clone().into())), span, &[])
+        }
+        Ok(left)
     }
-}
\ No newline at end of file
+}
@@ -1,277 +1,685 @@
-use lexer::token::Token;
-use lexer::enums::{TokenType};
+use ast::node::{Node, Program, Statement, Declaration, VariableDeclaration, VariableDeclarator, VariableKind, Expression, Pattern, MethodKind};
+use lexer::token::{Token, Position, Span, Comment};
+use lexer::enums::{TokenType, LiteralType, Goal, SourceType, Contextual};
+use lexer::state::LexerState;
 use error::JsResult;
 use error::error::{Error, ErrorType, SyntaxErrorType};
-use std::iter::Peekable;
-use std::vec::IntoIter;
-
-pub type TokenPeekable = Peekable<Box<IntoIter<Token>>>;
-
+use scope::expr::identifier_name;
+use scope::pattern::find_binding;
+use std::collections::{HashMap, VecDeque};
+use std::mem;
 struct Scope {}
 
-#[derive(PartialEq, Debug, Clone)]
-pub enum Item {
-    Item,
-    None
+/// The private names of a class body. Uses are checked when the body ends, since they may come
+/// before the declaration.
+#[derive(Default)]
+pub struct PrivateNames {
+    /// Whether each name is a lone getter or setter, which may still get its pair, and static.
+    pub declared: HashMap<String, (Option<MethodKind>, bool)>,
+    pub used: Vec<Node<String>>
 }
 
-pub struct Parser {
-    tokens: Vec<Token>,
-    len: usize,
-    index: usize,
+/// Errors in an expression that depend on whether it becomes a pattern, kept until that is known.
+#[derive(Default, Clone, Copy)]
+pub struct CoverErrors {
+    /// The `=` of a `{ a = 1 }` shorthand, only valid when the object becomes a pattern.
+    pub shorthand_init: Option<Span>,
+    /// A parenthesized expression other than an identifier or member, which can not become a
+    /// pattern.
+    pub parenthesized: Option<Span>,
+    /// A parenthesized identifier or member, which can become an assignment target but not the
+    /// parameter of an arrow function.
+    pub parenthesized_simple: Option<Span>
+}
+
+pub struct Parser<'a> {
+    lexer: LexerState<'a>,
+    /// Tokens looked at but not consumed yet, lexed on demand so it rarely holds more than two.
+    tokens: VecDeque<Token<'a>>,
+    end: Position,
+    /// Error the lexer failed with while the parser was looking ahead.
+    error: Option<Error>,
     scopes: Vec<Scope>,
+    /// One entry for each class body the parser is in.
+    pub private_names: Vec<PrivateNames>,
+    /// Set in modules and after a `"use strict"` directive.
+    pub strict: bool,
+    pub module: bool,
+    /// Set inside a generator body, where `yield` is a keyword.
+    pub generator: bool,
+    /// Set inside an async function body, where `await` is a keyword.
+    pub is_async: bool,
+    /// Set inside a function body, where `return` is allowed.
+    pub in_function: bool,
+    /// Set inside a `static` block, where `await` is reserved.
+    pub static_block: bool,
+    /// Set in a field initializer or a `static` block, where `arguments` is not allowed.
+    pub class_init: bool,
+    /// Set in methods, field initializers and `static` blocks, where `super.x` is allowed.
+    pub super_property: bool,
+    /// Set in the constructor of a class that `extends` another, where `super()` is allowed.
+    pub super_call: bool,
+    /// Set in functions, field initializers and `static` blocks, where `new.target` is allowed.
+    pub new_target: bool,
+    /// Set in the head of a `for` statement, where `in` is not a binary operator.
+    pub no_in: bool,
+    /// Cover errors of the expression being parsed.
+    pub cover: CoverErrors,
+    /// The first `yield` or `await` expression since the parameters being parsed started, which
+    /// they can not contain.
+    pub yield_await: Option<Node<&'static str>>,
+    /// Tokens `consume` looked for in vain at the next token, which `expected` reports too.
+    tried: Vec<&'static str>
+}
+
+/// Whether `token` can not continue an expression, so that a line break before it inserts a
+/// semicolon.
+fn ends_expression(token: &TokenType) -> bool {
+    match *token {
+        TokenType::In |
+        TokenType::Instanceof => false,
+        TokenType::Literal(_) |
+        TokenType::PrivateName(_) |
+        TokenType::LeftBrace |
+        TokenType::Invert |
+        TokenType::Tilde |
+        TokenType::Increment |
+        TokenType::Decrement => true,
+        ref token => identifier_name(token).is_some()
+    }
 }
 
-impl Parser {
-    fn new(tokens: Vec<Token>) -> Parser {
+impl<'a> Parser<'a> {
+    fn new(lexer: LexerState<'a>) -> Parser<'a> {
+        let module = lexer.source_type() == SourceType::Module;
         Parser {
-            tokens: tokens.clone(),
-            len: tokens.len(),
-            index: 0,
-            scopes: Vec::new()
+            lexer: lexer,
+            tokens: VecDeque::new(),
+            end: Position::default(),
+            error: None,
+            scopes: Vec::new(),
+            private_names: Vec::new(),
+            strict: module,
+            module: module,
+            generator: false,
+            is_async: false,
+            in_function: false,
+            static_block: false,
+            class_init: false,
+            super_property: false,
+            super_call: false,
+            new_target: false,
+            no_in: false,
+            cover: CoverErrors::default(),
+            yield_await: None,
+            tried: Vec::new()
+        }
+    }
+
+    /// Lexes until the token `index` tokens ahead is buffered, false when the input ends first.
+    /// Tokens are read in the div goal, `rescan_regex` switches one to the regex goal.
+    fn fill(&mut self, index: usize) -> bool {
+        while self.tokens.len() <= index {
+            if self.error.is_some() {
+                return false
+            }
+            match self.lexer.next_token(Goal::Div) {
+                Ok(Some(token)) => self.tokens.push_back(token),
+                Ok(None) => return false,
+                Err(err) => {
+                    self.error = Some(err);
+                    return false
+                }
+            }
+        }
+        true
+    }
+
+    /// Reads the `/` or `/=` token at the head again as a regex literal, for the places where the
+    /// grammar expects an expression.
+    pub fn rescan_regex(&mut self) -> JsResult<()> {
+        if self.tokens.len() != 1 {
+            return self.unexpected()
+        }
+        let slash = match self.tokens.pop_back() {
+            Some(slash) => slash,
+            None => return self.unexpected()
+        };
+        let token = try!(self.lexer.rescan_regex(slash));
+        self.tokens.push_back(token);
+        Ok(())
+    }
+
+    /// Looks through the directive prologue at the start of a program or function body and
+    /// switches to strict mode when it holds `"use strict"`.
+    pub fn parse_directives(&mut self) {
+        let mut index = 0;
+        loop {
+            match self.peek_at(index) {
+                Some(TokenType::Literal(LiteralType::String(_, raw))) => {
+                    let next = match self.peek_at(index + 1) {
+                        Some(TokenType::Semicolon) => index + 2,
+                        Some(TokenType::RightBrace) |
+                        None => index + 1,
+                        Some(ref token) if self.newline_before_at(index + 1) && ends_expression(token) => index + 1,
+                        _ => return
+                    };
+                    if raw == "\"use strict\"" || raw == "'use strict'" {
+                        self.strict = true
+                    }
+                    index = next
+                }
+                _ => return
+            }
+        }
+    }
+
+    /// Runs `parse` with `in` allowed again, for the brackets, calls and bodies nested in a
+    /// `for` head.
+    pub fn allow_in<T, F>(&mut self, parse: F) -> JsResult<T> where F: FnOnce(&mut Parser<'a>) -> JsResult<T> {
+        let outer = self.no_in;
+        self.no_in = false;
+        let result = parse(self);
+        self.no_in = outer;
+        result
+    }
+
+    /// Starts collecting the cover errors of a new expression, returns those of the enclosing one.
+    pub fn start_cover(&mut self) -> CoverErrors {
+        mem::replace(&mut self.cover, CoverErrors::default())
+    }
+
+    /// Goes back to the enclosing expression once the one just parsed is known not to become a
+    /// pattern, failing on its `{ a = 1 }`.
+    pub fn end_cover(&mut self, outer: CoverErrors) -> JsResult<()> {
+        let cover = mem::replace(&mut self.cover, outer);
+        match cover.shorthand_init {
+            Some(span) => self.error_at(SyntaxErrorType::Unexpected(TokenType::Equal), span, &[]),
+            None => Ok(())
         }
     }
 
+    /// Goes back to the enclosing expression, which takes over the cover errors of the one just
+    /// parsed since it may still become a pattern as a whole.
+    pub fn merge_cover(&mut self, outer: CoverErrors) {
+        let cover = mem::replace(&mut self.cover, outer);
+        self.cover.shorthand_init = self.cover.shorthand_init.or(cover.shorthand_init);
+        self.cover.parenthesized = self.cover.parenthesized.or(cover.parenthesized);
+        self.cover.parenthesized_simple = self.cover.parenthesized_simple.or(cover.parenthesized_simple);
+    }
+
+    /// Drops the parenthesized expressions from `start` on, they are the object of a member or
+    /// call now which can be an assignment target.
+    pub fn forget_parenthesized(&mut self, start: Position) {
+        if self.cover.parenthesized.map_or(false, |span| span.start.offset >= start.offset) {
+            self.cover.parenthesized = None
+        }
+        if self.cover.parenthesized_simple.map_or(false, |span| span.start.offset >= start.offset) {
+            self.cover.parenthesized_simple = None
+        }
+    }
+
+    /// Starts watching for `yield` and `await` expressions in parameters, returns the one found
+    /// before them.
+    pub fn start_parameters(&mut self) -> Option<Node<&'static str>> {
+        self.yield_await.take()
+    }
+
+    /// Fails on a `yield` or `await` expression in the parameters just parsed.
+    pub fn end_parameters(&mut self, outer: Option<Node<&'static str>>) -> JsResult<()> {
+        match mem::replace(&mut self.yield_await, outer) {
+            Some(keyword) => self.error_at(SyntaxErrorType::ParameterExpression(keyword.item), keyword.span, &[]),
+            None => Ok(())
+        }
+    }
+
+    /// Goes back to the enclosing expression once the parentheses just parsed turned out not to
+    /// be parameters.
+    pub fn merge_parameters(&mut self, outer: Option<Node<&'static str>>) {
+        self.yield_await = outer.or(self.yield_await.take());
+    }
+
     pub fn push_scope(&mut self) {
         self.scopes.push(Scope {})
     }
 
     pub fn bump(&mut self) -> JsResult<()> {
         println!("bump {:?}", self.peek());
-        self.index += 1;
-        if self.index > self.len {
-            Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF), 0, 0, None))
+        try!(self.next());
+        Ok(())
+    }
+
+    pub fn next(&mut self) -> JsResult<Token<'a>> {
+        println!("next {:?}", self.peek());
+        self.fill(0);
+        match self.tokens.pop_front() {
+            Some(token) => {
+                self.end = token.span.end;
+                self.tried.clear();
+                // checked when the token is consumed, a `"use strict"` directive may only be
+                // seen after it was lexed
+                if token.legacy_octal && self.strict {
+                    let what = match token.token {
+                        TokenType::Literal(LiteralType::String(..)) => "octal escape sequence",
+                        _ => "legacy octal literal"
+                    };
+                    return self.error_at(SyntaxErrorType::StrictMode(what), token.span, &[])
+                }
+                Ok(token)
+            }
+            None => {
+                let end = Span::new(self.end, self.end);
+                self.error_at(SyntaxErrorType::UnexpectedEOF, end, &[])
+            }
+        }
+    }
+
+    /// Start position of the next token, used as the start of the node parsed next.
+    pub fn position(&mut self) -> Position {
+        if self.fill(0) {
+            self.tokens[0].span.start
         } else {
-            Ok(())
+            self.end
         }
     }
 
-    pub fn next(&mut self) -> JsResult<Token> {
-        println!("next {:?}", self.peek());
-        self.index += 1;
-        if self.index > self.len {
-            Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF), 0, 0, None))
+    /// End position of the last consumed token.
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Wraps `item` in a node spanning from `start` to the end of the last consumed token.
+    pub fn finish<T>(&mut self, start: Position, item: T) -> Node<T> {
+        Node::new(Span::new(start, self.end), item)
+    }
+
+    /// Span of the next token, or an empty span after the last token at the end of the input.
+    pub fn next_span(&mut self) -> Span {
+        if self.fill(0) {
+            self.tokens[0].span
         } else {
-            Ok(self.tokens[self.index - 1].clone())
+            Span::new(self.end, self.end)
         }
     }
 
-    pub fn fatal(&mut self, error: SyntaxErrorType) -> JsResult<()> {
-        return Err(Error::new(ErrorType::SyntaxError(error), 0, 0, None))
+    pub fn error_at<T>(&mut self, error: SyntaxErrorType, span: Span, expected: &[&'static str]) -> JsResult<T> {
+        // the parser only sees a lexer error as the end of the input, report the cause instead
+        if let Some(err) = self.error.take() {
+            return Err(err)
+        }
+        Err(Error::new(ErrorType::SyntaxError(error), span, expected.to_vec()))
     }
 
+    /// Fails with `error` located at the next token.
+    pub fn fatal<T>(&mut self, error: SyntaxErrorType) -> JsResult<T> {
+        let span = self.next_span();
+        self.error_at(error, span, &[])
+    }
 
-    pub fn expect(&mut self, token: TokenType) -> JsResult<()> {
-        let next = try!(self.next());
-        println!("expected: {:?} == {:?}", token.clone(), next.clone());
+    pub fn unexpected<T>(&mut self) -> JsResult<T> {
+        self.expected(&[])
+    }
 
-        if next.token == token {
-            return Ok(());
+    /// Fails on the next token, `expected` lists the tokens or productions accepted instead
+    /// along with the tokens that were tried there before.
+    pub fn expected<T>(&mut self, expected: &[&'static str]) -> JsResult<T> {
+        let error = match self.peek() {
+            Some(t) => SyntaxErrorType::Unexpected(t.into_owned()),
+            None => SyntaxErrorType::UnexpectedEOF
+        };
+        let span = self.next_span();
+        let mut alternatives = mem::replace(&mut self.tried, Vec::new());
+        for item in expected {
+            if !alternatives.contains(item) {
+                alternatives.push(*item)
+            }
         }
+        self.error_at(error, span, &alternatives)
+    }
 
-        self.fatal(SyntaxErrorType::Unexpected(next.token))
+    pub fn expect(&mut self, token: TokenType) -> JsResult<()> {
+        if try!(self.consume(token.clone())) {
+            return Ok(());
+        }
+        self.expected(&[token.description()])
     }
 
     pub fn consume(&mut self, token: TokenType) -> JsResult<bool> {
         println!("consume: {:?} == {:?}", token.clone(), self.peek());
-        let matched = match self.peek() {
-            None => false,
-            Some(t) => t == token
-        };
+        let matched = self.peek_is(&token);
 
         if matched {
             try!(self.bump());
+        } else {
+            let description = token.description();
+            if !self.tried.contains(&description) {
+                self.tried.push(description)
+            }
         }
 
         Ok(matched)
     }
 
-    pub fn peek(&mut self) -> Option<TokenType> {
+    /// Like `consume`, for a token that would continue an expression. Any operator could do
+    /// that as well, so the token is not reported as an alternative.
+    pub fn consume_continuation(&mut self, token: TokenType) -> JsResult<bool> {
+        let matched = self.peek_is(&token);
+        if matched {
+            try!(self.bump());
+        }
+        Ok(matched)
+    }
+
+    /// Ends a statement, inserting the semicolon before a `}`, at the end of the input or
+    /// after a line break.
+    pub fn consume_semicolon(&mut self) -> JsResult<()> {
+        match self.peek() {
+            Some(TokenType::Semicolon) => self.bump(),
+            Some(TokenType::RightBrace) |
+            None => Ok(()),
+            Some(_) if self.newline_before() => Ok(()),
+            Some(_) => self.expected(&["`;`"])
+        }
+    }
+
+    /// Whether a line terminator comes before the next token, false at the end of the input.
+    pub fn newline_before(&mut self) -> bool {
+        self.newline_before_at(0)
+    }
+
+    pub fn newline_before_at(&mut self, index: usize) -> bool {
+        self.fill(index) && self.tokens[index].newline_before
+    }
+
+    /// Fails when a restricted production like `throw` or `=>` is followed by a line break.
+    pub fn no_newline_before(&mut self) -> JsResult<()> {
+        if self.newline_before() {
+            return self.fatal(SyntaxErrorType::UnexpectedEOL)
+        }
+        Ok(())
+    }
+
+    pub fn peek(&mut self) -> Option<TokenType<'a>> {
         self.peek_at(0)
     }
 
-    pub fn peek_at(&mut self, index: usize) -> Option<TokenType> {
-        if self.index + index >= self.len {
+    pub fn peek_at(&mut self, index: usize) -> Option<TokenType<'a>> {
+        if !self.fill(index) {
             None
         } else {
-            let token = self.tokens[self.index + index].clone();
-            Some(token.token)
+            Some(self.tokens[index].token.clone())
         }
     }
 
-    pub fn from_tokens(tokens: Vec<Token>) -> JsResult<()> {
-        let parser = &mut Parser::new(tokens);
+    /// Whether the next token is `token`, without copying it like `peek`.
+    pub fn peek_is(&mut self, token: &TokenType) -> bool {
+        self.fill(0) && self.tokens[0].token == *token
+    }
+
+    pub fn from_lexer(lexer: LexerState<'a>) -> JsResult<Program> {
+        let parser = &mut Parser::new(lexer);
         parser.push_scope();
-        parser.parse_stmt_list()
+        parser.parse_directives();
+        let body = try!(parser.parse_stmt_list());
+        match parser.peek() {
+            None => match parser.error.take() {
+                Some(err) => Err(err),
+                None => Ok(Program {
+                    body: body,
+                    comments: parser.lexer.take_comments().into_iter().map(Comment::into_owned).collect(),
+                    source_type: parser.lexer.source_type()
+                })
+            },
+            Some(t) => parser.fatal(SyntaxErrorType::Unexpected(t.into_owned()))
+        }
     }
 
-    pub fn parse_variable(&mut self) -> JsResult<Item> {
-        println!("parse_variable {:?}", self.peek());
-        try!(self.bump());
+    pub fn parse_variable(&mut self) -> JsResult<Node<Statement>> {
+        self.parse_variable_statement(VariableKind::Var)
+    }
 
-        try!(self.parse_variable_declaration());
-        while try!(self.consume(TokenType::Comma)) {
-            try!(self.parse_variable_declaration());
-        }
-        if !try!(self.consume(TokenType::LineTerminate)) {
-            try!(self.expect(TokenType::Semicolon));
-        }
-        Ok(Item::Item)
+    pub fn parse_let(&mut self) -> JsResult<Node<Statement>> {
+        self.parse_variable_statement(VariableKind::Let)
     }
 
-    pub fn parse_initializer(&mut self) -> JsResult<Item> {
-        println!("parse_initializer {:?}", self.peek());
-        if !try!(self.consume(TokenType::Equal)) {
-            Ok(Item::None)
-        } else {
-            self.parse_assign_expr()
-        }
+    pub fn parse_const(&mut self) -> JsResult<Node<Statement>> {
+        self.parse_variable_statement(VariableKind::Const)
     }
 
-    pub fn parse_variable_declaration(&mut self) -> JsResult<Item> {
-        println!("parse_variable_declaration {:?}", self.peek());
-        match self.peek() {
-            Some(TokenType::Identifier(_)) => {
-                try!(self.bump());
+    /// A `var` statement or a `let` or `const` declaration, up to the semicolon.
+    fn parse_variable_statement(&mut self, kind: VariableKind) -> JsResult<Node<Statement>> {
+        let start = self.position();
+        let declaration = try!(self.parse_variable_declaration_list(kind));
+        try!(self.check_initializers(&declaration));
+        try!(self.consume_semicolon());
+        Ok(self.finish(start, Statement::Declaration(Declaration::Variable(declaration.item))))
+    }
 
-                return self.parse_initializer()
-            }
-            Some(TokenType::LeftBrace) => return Ok(Item::Item),
-            Some(TokenType::LeftBracket) => return Ok(Item::Item),
-            Some(t) => {
-                try!(self.fatal(SyntaxErrorType::Unexpected(t)));
-            }
-            None => {
-                try!(self.fatal(SyntaxErrorType::UnexpectedEOF));
+    /// Fails on a `const` or destructuring declarator without an initializer, which only the head
+    /// of a `for-in` or `for-of` may leave out.
+    pub fn check_initializers(&mut self, declaration: &Node<VariableDeclaration>) -> JsResult<()> {
+        for declarator in &declaration.item.declarations {
+            let pattern = match declarator.item.id.item {
+                Pattern::Identifier(_) => false,
+                _ => true
+            };
+            if declarator.item.init.is_none() && (declaration.item.kind == VariableKind::Const || pattern) {
+                return self.error_at(SyntaxErrorType::MissingInitializer, declarator.span, &["`=`"])
             }
         }
-        Ok(Item::Item)
+        Ok(())
     }
-    pub fn parse_continue(&mut self) -> JsResult<Item> {
+
+    /// The keyword and comma separated declarators of a declaration, without the semicolon.
+    pub fn parse_variable_declaration_list(&mut self, kind: VariableKind) -> JsResult<Node<VariableDeclaration>> {
+        let start = self.position();
         try!(self.bump());
-        if try!(self.consume(TokenType::Semicolon)) {
-            Ok(Item::Item)
-        } else {
-            match self.peek() {
-                Some(TokenType::Identifier(_)) => (),
-                Some(t) => {
-                    return Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::Unexpected(t)), 0, 0, None))
-                }
-                None => {
-                    return Err(Error::new(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF), 0, 0, None))
+        let mut declarations = vec![try!(self.parse_variable_declaration())];
+        while try!(self.consume(TokenType::Comma)) {
+            declarations.push(try!(self.parse_variable_declaration()));
+        }
+        if kind != VariableKind::Var {
+            // `let` can not be the name of a lexical binding, not even outside strict mode
+            for declarator in &declarations {
+                if let Some(span) = find_binding(&declarator.item.id, "let") {
+                    return self.error_at(SyntaxErrorType::ReservedWord(String::from("let")), span, &[])
                 }
             }
-            try!(self.expect(TokenType::Semicolon));
-            Ok(Item::Item)
         }
+        Ok(self.finish(start, VariableDeclaration {
+            kind: kind,
+            declarations: declarations
+        }))
     }
 
-    pub fn parse_return(&mut self) -> JsResult<Item> {
-        try!(self.bump());
-        if try!(self.consume(TokenType::Semicolon)) {
-            Ok(Item::Item)
+    pub fn parse_initializer(&mut self) -> JsResult<Option<Node<Expression>>> {
+        println!("parse_initializer {:?}", self.peek());
+        if !try!(self.consume(TokenType::Equal)) {
+            Ok(None)
         } else {
-            let result = try!(self.parse_expr());
-            match self.peek() {
-                Some(TokenType::Semicolon) => try!(self.bump()),
-                Some(TokenType::LineTerminate) => try!(self.bump()),
-                Some(TokenType::RightBrace) => (),
-                _ => ()
-            }
-            Ok(result)
+            self.expect_assign_expr().map(Some)
         }
     }
 
-    pub fn parse_with(&mut self) -> JsResult<Item> {
-        try!(self.bump());
-        try!(self.expect(TokenType::LeftParen));
-        try!( self.parse_expr());
-        try!( self.expect(TokenType::RightParen));
-        self.parse_stmt()
+    pub fn parse_variable_declaration(&mut self) -> JsResult<Node<VariableDeclarator>> {
+        println!("parse_variable_declaration {:?}", self.peek());
+        let start = self.position();
+        let id = try!(self.parse_binding_target());
+        let init = try!(self.parse_initializer());
+        Ok(self.finish(start, VariableDeclarator {
+            id: id,
+            init: init
+        }))
     }
 
-    pub fn parse_do(&mut self) -> JsResult<Item> {
-        try!(self.bump());
-        Ok(Item::Item)
+    /// The label of a `break` or `continue`, which has to be on the same line.
+    fn parse_jump_label(&mut self) -> JsResult<Option<Node<String>>> {
+        if self.newline_before() {
+            return Ok(None)
+        }
+        self.consume_identifier()
     }
 
-    pub fn parse_while(&mut self) -> JsResult<Item> {
+    pub fn parse_continue(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
         try!(self.bump());
-        try!(self.expect(TokenType::LeftParen));
-        try!(self.parse_expr());
-        try!(self.expect(TokenType::RightParen));
-        self.parse_stmt()
+        let label = try!(self.parse_jump_label());
+        try!(self.consume_semicolon());
+        Ok(self.finish(start, Statement::Continue(label)))
     }
 
-    pub fn parse_for(&mut self) -> JsResult<Item> {
+    pub fn parse_return(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
+        if !self.in_function {
+            return self.unexpected()
+        }
         try!(self.bump());
-        Ok(Item::Item)
+        let argument = match self.peek() {
+            Some(TokenType::Semicolon) |
+            Some(TokenType::RightBrace) |
+            None => None,
+            _ if self.newline_before() => None,
+            _ => Some(try!(self.parse_expr()))
+        };
+        try!(self.consume_semicolon());
+        Ok(self.finish(start, Statement::Return(argument)))
     }
 
-    pub fn parse_debugger(&mut self) -> JsResult<Item> {
+    pub fn parse_with(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
+        if self.strict {
+            return self.fatal(SyntaxErrorType::StrictMode("`with` statement"))
+        }
         try!(self.bump());
-        try!(self.expect(TokenType::Semicolon));
-        Ok(Item::Item)
+        try!(self.expect(TokenType::LeftParen));
+        let object = try!(self.parse_expr());
+        try!(self.expect(TokenType::RightParen));
+        let body = try!(self.expect_stmt());
+        Ok(self.finish(start, Statement::With {
+            object: object,
+            body: Box::new(body)
+        }))
     }
 
-    pub fn parse_break(&mut self) -> JsResult<Item> {
+    pub fn parse_do(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
         try!(self.bump());
-        try!(self.consume_identifier());
-        try!(self.expect(TokenType::Semicolon));
-        Ok(Item::Item)
+        let body = try!(self.expect_stmt());
+        try!(self.expect(TokenType::While));
+        try!(self.expect(TokenType::LeftParen));
+        let test = try!(self.parse_expr());
+        try!(self.expect(TokenType::RightParen));
+        // a semicolon is inserted after `do-while` even on the same line
+        try!(self.consume(TokenType::Semicolon));
+        Ok(self.finish(start, Statement::DoWhile {
+            body: Box::new(body),
+            test: test
+        }))
     }
 
-    pub fn parse_class(&mut self) -> JsResult<Item> {
+    pub fn parse_while(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
         try!(self.bump());
-        Ok(Item::Item)
+        try!(self.expect(TokenType::LeftParen));
+        let test = try!(self.parse_expr());
+        try!(self.expect(TokenType::RightParen));
+        let body = try!(self.expect_stmt());
+        Ok(self.finish(start, Statement::While {
+            test: test,
+            body: Box::new(body)
+        }))
     }
 
-    pub fn parse_binding_list(&mut self) -> JsResult<Item> {
-        Ok(Item::Item)
+    pub fn parse_debugger(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
+        try!(self.bump());
+        try!(self.consume_semicolon());
+        Ok(self.finish(start, Statement::Debugger))
     }
 
-    pub fn parse_let(&mut self) -> JsResult<Item> {
+    pub fn parse_break(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
         try!(self.bump());
-        self.parse_binding_list()
+        let label = try!(self.parse_jump_label());
+        try!(self.consume_semicolon());
+        Ok(self.finish(start, Statement::Break(label)))
     }
 
-    pub fn parse_const(&mut self) -> JsResult<Item> {
-        try!(self.bump());
-        self.parse_binding_list()
+    /// Whether a `let` at the start of a statement begins a declaration rather than an
+    /// expression using `let` as an identifier.
+    pub fn at_let_declaration(&mut self) -> bool {
+        if self.peek() != Some(TokenType::Contextual(Contextual::Let)) {
+            return false
+        }
+        match self.peek_at(1) {
+            Some(TokenType::Identifier(_)) |
+            Some(TokenType::Contextual(_)) |
+            Some(TokenType::LeftBracket) |
+            Some(TokenType::LeftBrace) => true,
+            _ => false
+        }
     }
 
-    pub fn parse_if(&mut self) -> JsResult<Item> {
+    pub fn parse_if(&mut self) -> JsResult<Node<Statement>> {
         println!("parse_if {:?}", self.peek());
+        let start = self.position();
         try!(self.bump());
         try!(self.expect(TokenType::LeftParen));
-        try!(self.parse_expr());
+        let test = try!(self.parse_expr());
         try!(self.expect(TokenType::RightParen));
 
-        let then = try!(self.parse_stmt());
-        match then {
-            Item::Item => (),
-            Item::None => try!(self.fatal(SyntaxErrorType::UnexpectedEOF))
-        }
+        let consequent = try!(self.expect_stmt());
 
-        if try!(self.consume(TokenType::Else)) {
-            try!(self.parse_stmt());
-        }
-        Ok(Item::Item)
+        let alternate = if try!(self.consume(TokenType::Else)) {
+            Some(Box::new(try!(self.expect_stmt())))
+        } else {
+            None
+        };
+        Ok(self.finish(start, Statement::If {
+            test: test,
+            consequent: Box::new(consequent),
+            alternate: alternate
+        }))
     }
 
-    pub fn parse_element_list(&mut self) -> JsResult<Item> {
-        if !try!(self.consume(TokenType::ThreePoints)) {
-            try!(self.parse_expr());
-            try!(self.consume(TokenType::ThreePoints));
+    pub fn parse_element_list(&mut self) -> JsResult<Vec<Option<Node<Expression>>>> {
+        let mut elements = Vec::new();
+        loop {
+            match self.peek() {
+                Some(TokenType::RightBracket) => break,
+                Some(TokenType::Comma) => {
+                    try!(self.bump());
+                    elements.push(None);
+                    continue
+                }
+                _ => ()
+            }
+            let element = if self.peek() == Some(TokenType::ThreePoints) {
+                try!(self.parse_spread_element())
+            } else {
+                try!(self.expect_assign_expr_or_pattern())
+            };
+            elements.push(Some(element));
+            if !try!(self.consume(TokenType::Comma)) {
+                break
+            }
         }
-        Ok(Item::Item)
+        Ok(elements)
+    }
+
+    pub fn parse_spread_element(&mut self) -> JsResult<Node<Expression>> {
+        let start = self.position();
+        try!(self.expect(TokenType::ThreePoints));
+        let argument = try!(self.expect_assign_expr_or_pattern());
+        Ok(self.finish(start, Expression::Spread(Box::new(argument))))
     }
 
-    pub fn parse_array_literal(&mut self) -> JsResult<Item> {
+    pub fn parse_array_literal(&mut self) -> JsResult<Option<Node<Expression>>> {
         println!("parse_array_literal {:?}", self.peek());
+        let start = self.position();
         try!(self.expect(TokenType::LeftBracket));
-        try!(self.parse_element_list());
+        let elements = try!(self.parse_element_list());
         try!(self.expect(TokenType::RightBracket));
-        Ok(Item::Item)
+        Ok(Some(self.finish(start, Expression::Array(elements))))
     }
 }
\ No newline at end of file
@@ -0,0 +1,279 @@
+use ast::node::{Node, Expression, Pattern, PatternProperty, Property, PropertyKind, AssignmentOperator};
+use error::JsResult;
+use error::error::SyntaxErrorType;
+use lexer::enums::{TokenType};
+use lexer::token::Span;
+use scope::expr::identifier;
+use scope::parser::{Parser};
+
+/// Where `pattern` binds `name`, if it does.
+pub fn find_binding(pattern: &Node<Pattern>, name: &str) -> Option<Span> {
+    match pattern.item {
+        Pattern::Identifier(ref binding) if binding == name => Some(pattern.span),
+        Pattern::Identifier(_) |
+        Pattern::Expression(_) => None,
+        Pattern::Object(ref properties) => properties.iter().filter_map(|property| match property.item {
+            PatternProperty::Property { ref value, .. } => find_binding(value, name),
+            PatternProperty::Rest(ref argument) => find_binding(argument, name)
+        }).next(),
+        Pattern::Array(ref elements) => {
+            elements.iter().filter_map(|element| element.as_ref().and_then(|element| find_binding(element, name))).next()
+        }
+        Pattern::Rest(ref argument) => find_binding(argument, name),
+        Pattern::Assignment { ref left, .. } => find_binding(left, name)
+    }
+}
+
+/// Adds every name that `pattern` binds to `names`, in source order.
+pub fn bound_names<'p>(pattern: &'p Node<Pattern>, names: &mut Vec<Node<&'p str>>) {
+    match pattern.item {
+        Pattern::Identifier(ref name) => names.push(Node::new(pattern.span, name.as_str())),
+        Pattern::Expression(_) => (),
+        Pattern::Object(ref properties) => for property in properties {
+            match property.item {
+                PatternProperty::Property { ref value, .. } => bound_names(value, names),
+                PatternProperty::Rest(ref argument) => bound_names(argument, names)
+            }
+        },
+        Pattern::Array(ref elements) => for element in elements.iter().filter_map(|element| element.as_ref()) {
+            bound_names(element, names)
+        },
+        Pattern::Rest(ref argument) => bound_names(argument, names),
+        Pattern::Assignment { ref left, .. } => bound_names(left, names)
+    }
+}
+
+/// The first member expression a `pattern` assigns to, which a binding pattern can not have.
+fn member_in(pattern: &Node<Pattern>) -> Option<Span> {
+    match pattern.item {
+        Pattern::Identifier(_) => None,
+        Pattern::Expression(_) => Some(pattern.span),
+        Pattern::Object(ref properties) => properties.iter().filter_map(|property| {
+            match property.item {
+                PatternProperty::Property { ref value, .. } => member_in(value),
+                PatternProperty::Rest(ref argument) => member_in(argument)
+            }
+        }).next(),
+        Pattern::Array(ref elements) => {
+            elements.iter().filter_map(|element| element.as_ref().and_then(member_in)).next()
+        }
+        Pattern::Rest(ref argument) => member_in(argument),
+        Pattern::Assignment { ref left, .. } => member_in(left)
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn parse_binding_target(&mut self) -> JsResult<Node<Pattern>> {
+        let start = self.position();
+        if let Some(name) = try!(self.peek_identifier()) {
+            try!(self.bump());
+            return Ok(self.finish(start, Pattern::Identifier(name)))
+        }
+        match self.peek() {
+            Some(TokenType::LeftBrace) => self.allow_in(|p| p.parse_object_binding_pattern()),
+            Some(TokenType::LeftBracket) => self.allow_in(|p| p.parse_array_binding_pattern()),
+            _ => self.expected(&["identifier", "`{`", "`[`"])
+        }
+    }
+
+    pub fn parse_binding_element(&mut self) -> JsResult<Node<Pattern>> {
+        let target = try!(self.parse_binding_target());
+        match try!(self.parse_initializer()) {
+            Some(init) => Ok(self.finish(target.span.start, Pattern::Assignment {
+                left: Box::new(target),
+                right: Box::new(init)
+            })),
+            None => Ok(target)
+        }
+    }
+
+    pub fn parse_object_binding_pattern(&mut self) -> JsResult<Node<Pattern>> {
+        let start = self.position();
+        try!(self.expect(TokenType::LeftBrace));
+        let mut properties = Vec::new();
+        loop {
+            let property_start = self.position();
+            match self.peek() {
+                Some(TokenType::RightBrace) => break,
+                Some(TokenType::ThreePoints) => {
+                    try!(self.bump());
+                    let name = try!(self.expect_identifier());
+                    let argument = Node::new(name.span, Pattern::Identifier(name.item));
+                    properties.push(self.finish(property_start, PatternProperty::Rest(argument)));
+                    break
+                }
+                _ => ()
+            }
+            let shorthand = self.peek().as_ref().and_then(identifier);
+            let key = try!(self.parse_property_name());
+            let property = if try!(self.consume(TokenType::Colon)) {
+                PatternProperty::Property {
+                    key: key,
+                    value: try!(self.parse_binding_element()),
+                    shorthand: false
+                }
+            } else {
+                let name = match shorthand {
+                    Some(name) => name,
+                    None => return self.expected(&["`:`"])
+                };
+                try!(self.check_identifier(&name, key.span));
+                let target = Node::new(key.span, Pattern::Identifier(name));
+                let value = match try!(self.parse_initializer()) {
+                    Some(init) => self.finish(property_start, Pattern::Assignment {
+                        left: Box::new(target),
+                        right: Box::new(init)
+                    }),
+                    None => target
+                };
+                PatternProperty::Property {
+                    key: key,
+                    value: value,
+                    shorthand: true
+                }
+            };
+            properties.push(self.finish(property_start, property));
+            if !try!(self.consume(TokenType::Comma)) {
+                break
+            }
+        }
+        try!(self.expect(TokenType::RightBrace));
+        Ok(self.finish(start, Pattern::Object(properties)))
+    }
+
+    pub fn parse_array_binding_pattern(&mut self) -> JsResult<Node<Pattern>> {
+        let start = self.position();
+        try!(self.expect(TokenType::LeftBracket));
+        let mut elements = Vec::new();
+        loop {
+            match self.peek() {
+                Some(TokenType::RightBracket) => break,
+                Some(TokenType::Comma) => {
+                    try!(self.bump());
+                    elements.push(None);
+                    continue
+                }
+                Some(TokenType::ThreePoints) => {
+                    let rest_start = self.position();
+                    try!(self.bump());
+                    let rest = try!(self.parse_binding_target());
+                    elements.push(Some(self.finish(rest_start, Pattern::Rest(Box::new(rest)))));
+                    break
+                }
+                _ => ()
+            }
+            elements.push(Some(try!(self.parse_binding_element())));
+            if !try!(self.consume(TokenType::Comma)) {
+                break
+            }
+        }
+        try!(self.expect(TokenType::RightBracket));
+        Ok(self.finish(start, Pattern::Array(elements)))
+    }
+
+    pub fn simple_assignment_target(&mut self, expr: Node<Expression>) -> JsResult<Node<Pattern>> {
+        try!(self.check_simple_assignment_target(&expr));
+        let span = expr.span;
+        match expr.item {
+            Expression::Identifier(name) => Ok(Node::new(span, Pattern::Identifier(name))),
+            item => Ok(Node::new(span, Pattern::Expression(Box::new(Node::new(span, item)))))
+        }
+    }
+
+    /// The operand of `++`, `--` and compound assignments, which has to be an identifier or a
+    /// member expression.
+    pub fn check_simple_assignment_target(&mut self, expr: &Node<Expression>) -> JsResult<()> {
+        match expr.item {
+            Expression::Identifier(_) | Expression::Member { .. } => Ok(()),
+            _ => self.error_at(SyntaxErrorType::InvalidAssignmentTarget, expr.span, &[])
+        }
+    }
+
+    fn property_to_pattern(&mut self, property: Node<Property>) -> JsResult<Node<PatternProperty>> {
+        let span = property.span;
+        let property = property.item;
+        if property.kind != PropertyKind::Init || property.method {
+            return self.error_at(SyntaxErrorType::InvalidAssignmentTarget, span, &[])
+        }
+        let value = try!(self.expression_to_pattern(property.value));
+        Ok(Node::new(span, PatternProperty::Property {
+            key: property.key,
+            value: value,
+            shorthand: property.shorthand
+        }))
+    }
+
+    /// Turns the left side of `=`, the head of a `for-in` or `for-of` or the parameters of an
+    /// arrow function into a pattern, settling the cover errors collected while it was parsed.
+    pub fn assignment_target(&mut self, expr: Node<Expression>) -> JsResult<Node<Pattern>> {
+        if let Some(span) = self.cover.parenthesized {
+            return self.error_at(SyntaxErrorType::InvalidAssignmentTarget, span, &[])
+        }
+        match expr.item {
+            Expression::Object(_) | Expression::Array(_) => self.cover.shorthand_init = None,
+            _ => ()
+        }
+        self.expression_to_pattern(expr)
+    }
+
+    /// Turns a parameter of an arrow function into a pattern, which unlike an assignment target
+    /// only binds names and has no parenthesized parts.
+    pub fn arrow_parameter(&mut self, expr: Node<Expression>) -> JsResult<Node<Pattern>> {
+        if let Some(span) = self.cover.parenthesized_simple {
+            return self.error_at(SyntaxErrorType::InvalidAssignmentTarget, span, &[])
+        }
+        let pattern = try!(self.assignment_target(expr));
+        match member_in(&pattern) {
+            Some(span) => self.error_at(SyntaxErrorType::InvalidAssignmentTarget, span, &[]),
+            None => Ok(pattern)
+        }
+    }
+
+    /// The `...argument` of an array pattern or of parameters, which has to come last and can not
+    /// have an initializer.
+    pub fn rest_element(&mut self, span: Span, argument: Node<Pattern>, last: bool) -> JsResult<Node<Pattern>> {
+        if !last {
+            return self.error_at(SyntaxErrorType::InvalidAssignmentTarget, span, &[])
+        }
+        if let Pattern::Assignment { .. } = argument.item {
+            return self.error_at(SyntaxErrorType::InvalidAssignmentTarget, argument.span, &[])
+        }
+        Ok(Node::new(span, Pattern::Rest(Box::new(argument))))
+    }
+
+    pub fn expression_to_pattern(&mut self, expr: Node<Expression>) -> JsResult<Node<Pattern>> {
+        let span = expr.span;
+        match expr.item {
+            Expression::Array(elements) => {
+                let mut patterns = Vec::new();
+                let count = elements.len();
+                for (index, element) in elements.into_iter().enumerate() {
+                    let pattern = match element {
+                        Some(Node { span, item: Expression::Spread(argument) }) => {
+                            let argument = try!(self.expression_to_pattern(*argument));
+                            Some(try!(self.rest_element(span, argument, index + 1 == count)))
+                        }
+                        Some(element) => Some(try!(self.expression_to_pattern(element))),
+                        None => None
+                    };
+                    patterns.push(pattern);
+                }
+                Ok(Node::new(span, Pattern::Array(patterns)))
+            }
+            Expression::Object(properties) => {
+                let mut patterns = Vec::new();
+                for property in properties {
+                    patterns.push(try!(self.property_to_pattern(property)));
+                }
+                Ok(Node::new(span, Pattern::Object(patterns)))
+            }
+            Expression::Assignment { operator: AssignmentOperator::Equal, left, right } => {
+                Ok(Node::new(span, Pattern::Assignment {
+                    left: left,
+                    right: right
+                }))
+            }
+            item => self.simple_assignment_target(Node::new(span, item))
+        }
+    }
+}
@@ -1,58 +1,68 @@
+use ast::node::{Node, Statement, SwitchCase};
 use error::JsResult;
 use error::error::SyntaxErrorType;
 use lexer::enums::{TokenType};
-use scope::parser::{Parser, Item};
+use scope::parser::{Parser};
 
-impl Parser {
-    pub fn parse_case_clause(&mut self) -> JsResult<Item> {
+impl<'a> Parser<'a> {
+    pub fn parse_case_clause(&mut self) -> JsResult<Node<SwitchCase>> {
+        let start = self.position();
         try!(self.bump());
-        try!(self.parse_expr());
+        let test = try!(self.parse_expr());
         try!(self.expect(TokenType::Colon));
-        try!(self.parse_stmt_list());
-        Ok(Item::Item)
+        let consequent = try!(self.parse_stmt_list());
+        Ok(self.finish(start, SwitchCase {
+            test: Some(test),
+            consequent: consequent
+        }))
     }
 
-    pub fn parse_default_clause(&mut self) -> JsResult<Item> {
+    pub fn parse_default_clause(&mut self) -> JsResult<Node<SwitchCase>> {
+        let start = self.position();
         try!(self.bump());
         try!(self.expect(TokenType::Colon));
-        try!(self.parse_stmt_list());
-        Ok(Item::Item)
+        let consequent = try!(self.parse_stmt_list());
+        Ok(self.finish(start, SwitchCase {
+            test: None,
+            consequent: consequent
+        }))
     }
 
-    pub fn parse_case_block(&mut self) -> JsResult<Item> {
+    pub fn parse_case_block(&mut self) -> JsResult<Vec<Node<SwitchCase>>> {
         try!(self.expect(TokenType::LeftBrace));
+        let mut cases = Vec::new();
         let mut default = false;
         loop {
             match self.peek() {
                 Some(TokenType::Case) => {
-                    try!(self.parse_case_clause());
+                    cases.push(try!(self.parse_case_clause()));
                 },
                 Some(TokenType::Default) => {
                     if default {
-                        try!(self.fatal(SyntaxErrorType::Unexpected(TokenType::Default)));
+                        return self.fatal(SyntaxErrorType::Unexpected(TokenType::Default));
                     }
                     default = true;
-                    try!(self.parse_case_clause());
+                    cases.push(try!(self.parse_default_clause()));
                 },
                 Some(TokenType::RightBrace) => break,
-                Some(t) => {
-                    try!(self.fatal(SyntaxErrorType::Unexpected(t)));
-                }
-                None => {
-                    try!(self.fatal(SyntaxErrorType::UnexpectedEOF));
-                }
+                _ => return self.expected(&["`case`", "`default`", "`}`"])
             }
         }
 
         try!(self.expect(TokenType::RightBrace));
-        Ok(Item::Item)
+        Ok(cases)
     }
 
-    pub fn parse_switch(&mut self) -> JsResult<Item> {
+    pub fn parse_switch(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
         try!(self.bump());
         try!(self.expect(TokenType::LeftParen));
-        try!(self.parse_expr());
+        let discriminant = try!(self.parse_expr());
         try!(self.expect(TokenType::RightParen));
-        self.parse_case_block()
+        let cases = try!(self.parse_case_block());
+        Ok(self.finish(start, Statement::Switch {
+            discriminant: discriminant,
+            cases: cases
+        }))
     }
-}
\ No newline at end of file
+}
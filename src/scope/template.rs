@@ -0,0 +1,85 @@
+use ast::node::{Node, Expression, TemplateLiteral, TemplateElement};
+use error::JsResult;
+use error::error::SyntaxErrorType;
+use lexer::enums::{TokenType, TemplateString};
+use lexer::token::{Position, Span};
+use scope::parser::{Parser};
+
+impl<'a> Parser<'a> {
+    /// Builds an element from a template token, the element leaves out the backtick or `}`
+    /// opening the token and the `${` or backtick closing it.
+    fn template_element(&mut self, span: Span, s: TemplateString, tail: bool) -> Node<TemplateElement> {
+        let mut span = span;
+        let close = if tail { 1 } else { 2 };
+        span.start.offset += 1;
+        span.start.col += 1;
+        span.end.offset -= close;
+        span.end.col -= close as u32;
+        Node::new(span, TemplateElement {
+            cooked: s.cooked.map(|cooked| cooked.into_owned()),
+            raw: s.raw.into_owned(),
+            tail: tail
+        })
+    }
+
+    pub fn at_template(&mut self) -> bool {
+        match self.peek() {
+            Some(TokenType::NoSubstitutionTemplate(_)) |
+            Some(TokenType::TemplateHead(_)) => true,
+            _ => false
+        }
+    }
+
+    /// Only a `tagged` template may contain invalid escapes, its tag gets their raw text.
+    pub fn parse_template_literal(&mut self, tagged: bool) -> JsResult<Node<TemplateLiteral>> {
+        let start = self.position();
+        let span = self.next_span();
+        let mut quasis = Vec::new();
+        let mut expressions = Vec::new();
+        match self.peek() {
+            Some(TokenType::NoSubstitutionTemplate(s)) => {
+                try!(self.bump());
+                quasis.push(self.template_element(span, s, true));
+            }
+            Some(TokenType::TemplateHead(s)) => {
+                try!(self.bump());
+                quasis.push(self.template_element(span, s, false));
+                loop {
+                    expressions.push(try!(self.parse_expr()));
+                    let span = self.next_span();
+                    match self.peek() {
+                        Some(TokenType::TemplateMiddle(s)) => {
+                            try!(self.bump());
+                            quasis.push(self.template_element(span, s, false));
+                        }
+                        Some(TokenType::TemplateTail(s)) => {
+                            try!(self.bump());
+                            quasis.push(self.template_element(span, s, true));
+                            break
+                        }
+                        _ => return self.expected(&["`}`"])
+                    }
+                }
+            }
+            _ => return self.expected(&["template"])
+        }
+        if !tagged {
+            if let Some(span) = quasis.iter().find(|quasi| quasi.item.cooked.is_none()).map(|quasi| quasi.span) {
+                return self.error_at(SyntaxErrorType::InvalidEscape, span, &[])
+            }
+        }
+        Ok(self.finish(start, TemplateLiteral {
+            quasis: quasis,
+            expressions: expressions
+        }))
+    }
+
+    /// The template after `tag`, which starts at `start` with any parentheses around it.
+    pub fn parse_tagged_template(&mut self, start: Position, tag: Node<Expression>) -> JsResult<Node<Expression>> {
+        let quasi = try!(self.allow_in(|p| p.parse_template_literal(true)));
+        Ok(self.finish(start, Expression::TaggedTemplate {
+            tag: Box::new(tag),
+            quasi: quasi
+        }))
+    }
+}
@@ -1,49 +1,54 @@
+use ast::node::{Node, Statement, Pattern, CatchClause};
 use error::JsResult;
-use error::error::SyntaxErrorType;
 use lexer::enums::{TokenType};
-use scope::parser::{Parser, Item};
+use lexer::token::Span;
+use scope::parser::{Parser};
 
-impl Parser {
-    pub fn parse_catch_parameter(&mut self) -> JsResult<Item> {
-        match self.peek() {
-            Some(TokenType::Identifier(_)) => {
-                try!(self.bump());
-            }
-            Some(TokenType::LeftBrace) => return Ok(Item::Item),
-            Some(TokenType::LeftBracket) => return Ok(Item::Item),
-            Some(t) => {
-                try!(self.fatal(SyntaxErrorType::Unexpected(t)));
-            }
-            None => {
-                try!(self.fatal(SyntaxErrorType::UnexpectedEOF));
-            }
-        }
-        Ok(Item::Item)
+impl<'a> Parser<'a> {
+    pub fn parse_catch_parameter(&mut self) -> JsResult<Node<Pattern>> {
+        self.parse_binding_target()
     }
 
-    pub fn parse_try(&mut self) -> JsResult<Item> {
+    pub fn parse_try(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
         try!(self.bump());
-        try!(self.parse_block());
-        try!(self.consume_all_lineterminates());
-        if try!(self.consume(TokenType::Catch)) {
+        let block = try!(self.parse_block_body());
+        let handler_start = self.position();
+        let handler = if try!(self.consume(TokenType::Catch)) {
             try!(self.expect(TokenType::LeftParen));
-            try!(self.parse_catch_parameter());
+            let param = try!(self.parse_catch_parameter());
             try!(self.expect(TokenType::RightParen));
-            try!(self.parse_block());
-            try!(self.consume_all_lineterminates());
-        }
+            let body = try!(self.parse_block_body());
+            Some(Node::new(Span::new(handler_start, body.span.end), CatchClause {
+                param: param,
+                body: body
+            }))
+        } else {
+            None
+        };
 
-        if try!(self.consume(TokenType::Finally)) {
-            try!(self.parse_block());
-            try!(self.consume_all_lineterminates());
+        let finalizer = if try!(self.consume(TokenType::Finally)) {
+            let finalizer = try!(self.parse_block_body());
+            Some(finalizer)
+        } else {
+            None
+        };
+        if handler.is_none() && finalizer.is_none() {
+            return self.expected(&["`catch`", "`finally`"])
         }
-        Ok(Item::Item)
+        Ok(self.finish(start, Statement::Try {
+            block: block,
+            handler: handler,
+            finalizer: finalizer
+        }))
     }
 
-    pub fn parse_throw(&mut self) -> JsResult<Item> {
+    pub fn parse_throw(&mut self) -> JsResult<Node<Statement>> {
+        let start = self.position();
         try!(self.bump());
-        try!(self.parse_assign_expr());
-        try!(self.consume(TokenType::Semicolon));
-        Ok(Item::Item)
+        try!(self.no_newline_before());
+        let argument = try!(self.parse_expr());
+        try!(self.consume_semicolon());
+        Ok(self.finish(start, Statement::Throw(argument)))
     }
-}
\ No newline at end of file
+}
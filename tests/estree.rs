@@ -0,0 +1,96 @@
+extern crate js_parser_rs;
+
+use js_parser_rs::JsContext;
+use js_parser_rs::ast::estree;
+
+fn json(s: &str) -> String {
+    let program = JsContext::new().parse(String::from(s)).unwrap();
+    estree::to_json(&program, s)
+}
+
+#[test]
+fn test_program() {
+    let out = json("a;\nb");
+    assert!(out.starts_with("{\"type\":\"Program\",\"body\":[{\"type\":\"ExpressionStatement\""));
+    assert!(out.ends_with(",\"sourceType\":\"script\",\"loc\":{\"start\":{\"line\":1,\"column\":0},\"end\":{\"line\":2,\"column\":1}},\"range\":[0,4]}"));
+    assert_eq!(json(""), "{\"type\":\"Program\",\"body\":[],\"sourceType\":\"script\",\"loc\":{\"start\":{\"line\":1,\"column\":0},\"end\":{\"line\":1,\"column\":0}},\"range\":[0,0]}");
+    let program = JsContext::new().parse_module(String::from("a")).unwrap();
+    assert!(estree::to_json(&program, "a").contains(",\"sourceType\":\"module\","));
+}
+
+#[test]
+fn test_literals() {
+    let out = json("\"a\\\"b\"; 1; 1.5; true; null; a = /x/g");
+    assert!(out.contains("\"type\":\"Literal\",\"value\":\"a\\\"b\",\"raw\":\"\\\"a\\\\\\\"b\\\"\""));
+    assert!(out.contains("\"type\":\"Literal\",\"value\":1,\"raw\":\"1\""));
+    assert!(out.contains("\"type\":\"Literal\",\"value\":1.5,\"raw\":\"1.5\""));
+    assert!(out.contains("\"type\":\"Literal\",\"value\":true,\"raw\":\"true\""));
+    assert!(out.contains("\"type\":\"Literal\",\"value\":null,\"raw\":\"null\""));
+    assert!(out.contains("\"type\":\"Literal\",\"value\":null,\"regex\":{\"pattern\":\"x\",\"flags\":\"g\"},\"raw\":\"/x/g\""));
+    let out = json("10n; 0x1_0n");
+    assert!(out.contains("\"type\":\"Literal\",\"value\":null,\"bigint\":\"10\",\"raw\":\"10n\""));
+    assert!(out.contains("\"type\":\"Literal\",\"value\":null,\"bigint\":\"0x10\",\"raw\":\"0x1_0n\""));
+}
+
+#[test]
+fn test_locations() {
+    let out = json("a;\n  0x1F");
+    assert!(out.contains("{\"type\":\"Identifier\",\"name\":\"a\",\"loc\":{\"start\":{\"line\":1,\"column\":0},\"end\":{\"line\":1,\"column\":1}},\"range\":[0,1]}"));
+    assert!(out.contains("{\"type\":\"Literal\",\"value\":31,\"raw\":\"0x1F\",\"loc\":{\"start\":{\"line\":2,\"column\":2},\"end\":{\"line\":2,\"column\":6}},\"range\":[5,9]}"));
+    assert!(json("'x'").contains("\"raw\":\"'x'\""));
+
+    // ranges and columns count UTF-16 code units, like JavaScript strings
+    let out = json("x = \"é\"; y = '😀';\r\n  z");
+    assert!(out.contains("\"raw\":\"\\\"é\\\"\",\"loc\":{\"start\":{\"line\":1,\"column\":4},\"end\":{\"line\":1,\"column\":7}},\"range\":[4,7]}"));
+    assert!(out.contains("\"raw\":\"'😀'\",\"loc\":{\"start\":{\"line\":1,\"column\":13},\"end\":{\"line\":1,\"column\":17}},\"range\":[13,17]}"));
+    assert!(out.contains("{\"type\":\"Identifier\",\"name\":\"z\",\"loc\":{\"start\":{\"line\":2,\"column\":2},\"end\":{\"line\":2,\"column\":3}},\"range\":[22,23]}"));
+}
+
+#[test]
+fn test_template() {
+    let out = json("tag`a\\n${b}`");
+    assert!(out.contains("\"type\":\"TaggedTemplateExpression\",\"tag\":{\"type\":\"Identifier\""));
+    assert!(out.contains("{\"type\":\"TemplateElement\",\"value\":{\"raw\":\"a\\\\n\",\"cooked\":\"a\\n\"},\"tail\":false,\"loc\":{\"start\":{\"line\":1,\"column\":4},\"end\":{\"line\":1,\"column\":7}},\"range\":[4,7]}"));
+    assert!(out.contains("{\"type\":\"TemplateElement\",\"value\":{\"raw\":\"\",\"cooked\":\"\"},\"tail\":true,\"loc\":{\"start\":{\"line\":1,\"column\":11},\"end\":{\"line\":1,\"column\":11}},\"range\":[11,11]}"));
+    assert!(out.contains("\"type\":\"TemplateLiteral\",\"quasis\":["));
+}
+
+#[test]
+fn test_directive() {
+    let out = json("'use strict'; a; 'b'");
+    assert!(out.contains("\"directive\":\"use strict\""));
+    assert!(!out.contains("\"directive\":\"b\""));
+}
+
+#[test]
+fn test_node_types() {
+    let out = json("function* f(a, [b], {c: d, ...e}, f = 1, ...g) { yield a; return this }\n\
+                    var h = 1, l = { m() { return (i, j) => i ? new.target : super.k }, get n() {}, [o]: p, q };\n\
+                    r.s[t] += -u++ || v instanceof w, x = y;\n\
+                    lbl: while (a) { try { throw a } catch (e) { break lbl } finally { continue } }\n\
+                    switch (a) { case 1: default: debugger }\n\
+                    with (a) ;\n\
+                    for (;;) ; for (a in b) ; for (var c of d) ; do ; while (a)\n\
+                    class z extends a { constructor() {} static async m() { await (class {}) } #f = 1; static { #f in this } }");
+    for kind in &["FunctionDeclaration", "Identifier", "ArrayPattern", "ObjectPattern", "RestElement",
+                  "AssignmentPattern", "BlockStatement", "YieldExpression", "ReturnStatement", "ThisExpression",
+                  "VariableDeclaration", "VariableDeclarator", "ArrowFunctionExpression", "ConditionalExpression",
+                  "MetaProperty", "MemberExpression", "Super", "ObjectExpression", "Property", "FunctionExpression",
+                  "SequenceExpression", "AssignmentExpression", "LogicalExpression", "UnaryExpression",
+                  "UpdateExpression", "BinaryExpression", "LabeledStatement", "WhileStatement", "TryStatement",
+                  "ThrowStatement", "CatchClause", "BreakStatement", "ContinueStatement", "SwitchStatement",
+                  "SwitchCase", "DebuggerStatement", "WithStatement", "EmptyStatement", "ForStatement",
+                  "ForInStatement", "ForOfStatement", "DoWhileStatement"] {
+        assert!(out.contains(&format!("\"type\":\"{}\"", kind)), "missing {}", kind);
+    }
+    assert!(out.contains("\"kind\":\"get\""));
+    assert!(out.contains("\"kind\":\"constructor\""));
+    assert!(out.contains("\"static\":true"));
+    assert!(out.contains("\"async\":true"));
+    assert!(out.contains("\"method\":true"));
+    assert!(out.contains("\"shorthand\":true"));
+    assert!(out.contains("\"computed\":true"));
+    assert!(out.contains("\"expression\":true"));
+    assert!(out.contains("\"operator\":\"+=\""));
+    assert!(out.contains("\"operator\":\"instanceof\""));
+}
@@ -1,7 +1,10 @@
 extern crate js_parser_rs;
 
-use js_parser_rs::lexer::enums::{TokenType, LiteralType, RegexIdentifier};
+use js_parser_rs::lexer::enums::{TokenType, LiteralType, TemplateString, SourceType, Contextual, Goal};
+use js_parser_rs::lexer::state::LexerState;
+use js_parser_rs::lexer::token::{Token, CommentKind};
 use js_parser_rs::error::error::{ErrorType, SyntaxErrorType};
+use std::borrow::Cow;
 use std::fs::File;
 use std::io::Read;
 
@@ -26,20 +29,40 @@ fn test_white_spaces() {
     assert_eq!(js_parser_rs::parse("\u{b}".chars()), Ok(vec![]));
     assert_eq!(js_parser_rs::parse("\u{c}".chars()), Ok(vec![]));
     assert_eq!(js_parser_rs::parse("\t".chars()), Ok(vec![]));
+    assert_eq!(js_parser_rs::parse("\u{feff}\u{1680}\u{2003}\u{202f}\u{3000}".chars()), Ok(vec![]));
     assert_eq!(js_parser_rs::parse("; ".chars()), Ok(vec![TokenType::Semicolon]));
     assert_eq!(js_parser_rs::parse(",".chars()), Ok(vec![TokenType::Comma]));
 }
 
+fn string(value: &'static str, raw: &'static str) -> TokenType<'static> {
+    TokenType::Literal(LiteralType::String(Cow::from(value), Cow::from(raw)))
+}
+
 #[test]
 fn test_useless_string() {
-    assert_eq!(js_parser_rs::parse("+ \"-\\f]' ms=''>\" +\n".chars()), Ok(vec![TokenType::Plus,TokenType::Literal(LiteralType::String(String::from("-\\f]' ms=''>"))),TokenType::Plus,TokenType::LineTerminate]));
-    assert_eq!(js_parser_rs::parse("\"Hello World!\"".chars()), Ok(vec![TokenType::Literal(LiteralType::String(String::from("Hello World!")))]));
+    assert_eq!(js_parser_rs::parse("+ \"-\\f]' ms=''>\" +\n".chars()), Ok(vec![TokenType::Plus,string("-\u{c}]' ms=''>", "\"-\\f]' ms=''>\""),TokenType::Plus]));
+    assert_eq!(js_parser_rs::parse("\"Hello World!\"".chars()), Ok(vec![string("Hello World!", "\"Hello World!\"")]));
     assert_eq!(js_parser_rs::parse("\"Hello".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF)));
-    assert_eq!(js_parser_rs::parse("\"Hel{}\" \"Hello World!\"".chars()),Ok(vec![TokenType::Literal(LiteralType::String(String::from("Hel{}"))),TokenType::Literal(LiteralType::String(String::from("Hello World!")))]));
-    assert_eq!(js_parser_rs::parse("\"Hello\\\" World!\"".chars()), Ok(vec![TokenType::Literal(LiteralType::String(String::from("Hello\" World!")))]));
-    assert_eq!(js_parser_rs::parse("'Hello World!'".chars()), Ok(vec![TokenType::Literal(LiteralType::String(String::from("Hello World!")))]));
-    assert_eq!(js_parser_rs::parse("'Hello\\' World!'".chars()), Ok(vec![TokenType::Literal(LiteralType::String(String::from("Hello' World!")))]));
-    assert_eq!(js_parser_rs::parse("\"Hello\\\\ World!\"".chars()), Ok(vec![TokenType::Literal(LiteralType::String(String::from("Hello\\ World!")))]));
+    assert_eq!(js_parser_rs::parse("\"Hel{}\" \"Hello World!\"".chars()),Ok(vec![string("Hel{}", "\"Hel{}\""),string("Hello World!", "\"Hello World!\"")]));
+    assert_eq!(js_parser_rs::parse("\"Hello\\\" World!\"".chars()), Ok(vec![string("Hello\" World!", "\"Hello\\\" World!\"")]));
+    assert_eq!(js_parser_rs::parse("'Hello World!'".chars()), Ok(vec![string("Hello World!", "'Hello World!'")]));
+    assert_eq!(js_parser_rs::parse("'Hello\\' World!'".chars()), Ok(vec![string("Hello' World!", "'Hello\\' World!'")]));
+    assert_eq!(js_parser_rs::parse("\"Hello\\\\ World!\"".chars()), Ok(vec![string("Hello\\ World!", "\"Hello\\\\ World!\"")]));
+}
+
+#[test]
+fn test_string_escapes() {
+    assert_eq!(js_parser_rs::parse("'a\\nb'".chars()), Ok(vec![string("a\nb", "'a\\nb'")]));
+    assert_eq!(js_parser_rs::parse("'\\t\\b\\f\\v\\0\\r'".chars()), Ok(vec![string("\t\u{8}\u{c}\u{b}\0\r", "'\\t\\b\\f\\v\\0\\r'")]));
+    assert_eq!(js_parser_rs::parse("'\\x41\\u0042\\u{43}\\u{1F600}'".chars()), Ok(vec![string("ABC\u{1F600}", "'\\x41\\u0042\\u{43}\\u{1F600}'")]));
+    assert_eq!(js_parser_rs::parse("'\\uD83D\\uDE00'".chars()), Ok(vec![string("\u{1F600}", "'\\uD83D\\uDE00'")]));
+    assert_eq!(js_parser_rs::parse("'a\\\nb\\\r\nc'".chars()), Ok(vec![string("abc", "'a\\\nb\\\r\nc'")]));
+    assert_eq!(js_parser_rs::parse("'\\101\\0a\\08\\400\\9'".chars()), Ok(vec![string("A\0a\u{0}8 09", "'\\101\\0a\\08\\400\\9'")]));
+    assert_eq!(js_parser_rs::parse("'\\q\u{2028}'".chars()), Ok(vec![string("q\u{2028}", "'\\q\u{2028}'")]));
+    assert_eq!(js_parser_rs::parse("'a\nb'".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedString)));
+    assert_eq!(js_parser_rs::parse("'\\x4'".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidEscape)));
+    assert_eq!(js_parser_rs::parse("'\\u{110000}'".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidEscape)));
+    assert_eq!(js_parser_rs::parse("'\\u12'".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidEscape)));
 }
 
 #[test]
@@ -55,6 +78,52 @@ fn test_useless_number() {
     assert_eq!(js_parser_rs::parse("123456789.123".chars()), Ok(vec![TokenType::Literal(LiteralType::Float(123456789.123))]));
 }
 
+fn number(s: &str) -> Result<Vec<TokenType<'static>>, ErrorType> {
+    js_parser_rs::parse(String::from(s).chars().collect::<Vec<char>>())
+}
+
+fn int(i: i64) -> Result<Vec<TokenType<'static>>, ErrorType> {
+    Ok(vec![TokenType::Literal(LiteralType::Integer(i))])
+}
+
+fn float(f: f64) -> Result<Vec<TokenType<'static>>, ErrorType> {
+    Ok(vec![TokenType::Literal(LiteralType::Float(f))])
+}
+
+fn bigint(digits: &'static str) -> Result<Vec<TokenType<'static>>, ErrorType> {
+    Ok(vec![TokenType::Literal(LiteralType::BigInt(Cow::from(digits)))])
+}
+
+#[test]
+fn test_numeric_literals() {
+    assert_eq!(number("1e10"), float(1e10));
+    assert_eq!(number("1E+2"), float(100.0));
+    assert_eq!(number("2.5e-3"), float(2.5e-3));
+    assert_eq!(number("0e0"), float(0.0));
+    assert_eq!(number(".5"), float(0.5));
+    assert_eq!(number("5."), float(5.0));
+    assert_eq!(number("0b101"), int(5));
+    assert_eq!(number("0B1_0"), int(2));
+    assert_eq!(number("1_000_000"), int(1000000));
+    assert_eq!(number("0x_1"), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidNumber)));
+    assert_eq!(number("1__0"), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidNumber)));
+    assert_eq!(number("1_"), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidNumber)));
+    assert_eq!(number("1_.5"), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidNumber)));
+    assert_eq!(number("017"), int(15));
+    assert_eq!(number("08"), int(8));
+    assert_eq!(number("019.5"), float(19.5));
+    assert_eq!(number("0xFFFFFFFFFFFFFFFFFF"), float(4722366482869645213696.0));
+    assert_eq!(number("9007199254740993"), float(9007199254740992.0));
+    assert_eq!(number("10n"), bigint("10"));
+    assert_eq!(number("0n"), bigint("0"));
+    assert_eq!(number("0xFFFFFFFFFFFFFFFFFFn"), bigint("4722366482869645213695"));
+    assert_eq!(number("1.5n"), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('n'))));
+    assert_eq!(number("1e"), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidNumber)));
+    assert_eq!(number("3in"), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('i'))));
+    assert_eq!(number("0b12"), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('2'))));
+    assert_eq!(number("1..a"), Ok(vec![TokenType::Literal(LiteralType::Float(1.0)), TokenType::Point, TokenType::Identifier(Cow::from("a"))]));
+}
+
 #[test]
 fn test_literal() {
     assert_eq!(js_parser_rs::parse("true".chars()), Ok(vec![TokenType::Literal(LiteralType::Boolean(true))]));
@@ -114,29 +183,61 @@ fn test_punctuator() {
     assert_eq!(js_parser_rs::parse("&&".chars()), Ok(vec![TokenType::And]));
     assert_eq!(js_parser_rs::parse("**".chars()), Ok(vec![TokenType::Exp]));
     assert_eq!(js_parser_rs::parse("**=".chars()), Ok(vec![TokenType::ExpAssign]));
-    assert_eq!(js_parser_rs::parse("+-f".chars()), Ok(vec![TokenType::Plus,TokenType::Minus,TokenType::Identifier(String::from("f"))]));
+    assert_eq!(js_parser_rs::parse("+-f".chars()), Ok(vec![TokenType::Plus,TokenType::Minus,TokenType::Identifier(Cow::from("f"))]));
     assert_eq!(js_parser_rs::parse("..".chars()), Ok(vec![TokenType::Point,TokenType::Point]));
     assert_eq!(js_parser_rs::parse("...".chars()), Ok(vec![TokenType::ThreePoints]));
 }
 
+#[test]
+fn test_modern_punctuator() {
+    let a = TokenType::Identifier(Cow::from("a"));
+    let b = TokenType::Identifier(Cow::from("b"));
+    assert_eq!(js_parser_rs::parse("?.".chars()), Ok(vec![TokenType::OptionalChaining]));
+    assert_eq!(js_parser_rs::parse("??".chars()), Ok(vec![TokenType::Nullish]));
+    assert_eq!(js_parser_rs::parse("??=".chars()), Ok(vec![TokenType::NullishAssign]));
+    assert_eq!(js_parser_rs::parse("||=".chars()), Ok(vec![TokenType::OrAssign]));
+    assert_eq!(js_parser_rs::parse("&&=".chars()), Ok(vec![TokenType::AndAssign]));
+    assert_eq!(js_parser_rs::parse("???".chars()), Ok(vec![TokenType::Nullish, TokenType::QuestionMark]));
+    assert_eq!(js_parser_rs::parse("a?.b".chars()), Ok(vec![a.clone(), TokenType::OptionalChaining, b.clone()]));
+    assert_eq!(js_parser_rs::parse("a?.5:b".chars()), Ok(vec![
+        a.clone(),
+        TokenType::QuestionMark,
+        TokenType::Literal(LiteralType::Float(0.5)),
+        TokenType::Colon,
+        b.clone()
+    ]));
+    assert_eq!(js_parser_rs::parse("a?b:c".chars()), Ok(vec![
+        a.clone(),
+        TokenType::QuestionMark,
+        b.clone(),
+        TokenType::Colon,
+        TokenType::Identifier(Cow::from("c"))
+    ]));
+    assert_eq!(js_parser_rs::parse("a<<=b".chars()), Ok(vec![a.clone(), TokenType::LeftShiftAssign, b.clone()]));
+    assert_eq!(js_parser_rs::parse("a>>=b".chars()), Ok(vec![a.clone(), TokenType::RightShiftAssign, b.clone()]));
+    assert_eq!(js_parser_rs::parse("this.#a".chars()), Ok(vec![TokenType::This, TokenType::Point, TokenType::PrivateName(Cow::from("a"))]));
+    assert_eq!(js_parser_rs::parse("#\\u0062c in a".chars()), Ok(vec![TokenType::PrivateName(Cow::from("bc")), TokenType::In, a]));
+    assert_eq!(js_parser_rs::parse("# a".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('#'))));
+}
+
 #[test]
 fn test_raw() {
-    assert_eq!(js_parser_rs::parse("Hello".chars()), Ok(vec![TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello\n".chars()), Ok(vec![TokenType::Identifier(String::from("Hello")),TokenType::LineTerminate]));
-    assert_eq!(js_parser_rs::parse("Hello\r".chars()), Ok(vec![TokenType::Identifier(String::from("Hello")),TokenType::LineTerminate]));
-    assert_eq!(js_parser_rs::parse("Hello\u{a0}".chars()), Ok(vec![TokenType::Identifier(String::from("Hello"))]));
-    //assert_eq!(js_parser_rs::parse("Hello\u{9}".chars()), Ok(vec![TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello\u{b}".chars()), Ok(vec![TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello\u{c}".chars()), Ok(vec![TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello\t".chars()), Ok(vec![TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello ".chars()), Ok(vec![TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello=".chars()), Ok(vec![TokenType::Identifier(String::from("Hello")),TokenType::Equal]));
-    assert_eq!(js_parser_rs::parse("Hello('sd'".chars()), Ok(vec![TokenType::Identifier(String::from("Hello")),TokenType::LeftParen,TokenType::Literal(LiteralType::String(String::from("sd")))]));
-    assert_eq!(js_parser_rs::parse("Hello|Hello".chars()), Ok(vec![TokenType::Identifier(String::from("Hello")),TokenType::OrBitwise,TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello.Hello".chars()), Ok(vec![TokenType::Identifier(String::from("Hello")),TokenType::Point,TokenType::Identifier(String::from("Hello"))]));
-    assert_eq!(js_parser_rs::parse("Hello/Hello".chars()), Ok(vec![TokenType::Identifier(String::from("Hello")),TokenType::Divide,TokenType::Identifier(String::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello\n".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello\r".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello\u{a0}".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    //assert_eq!(js_parser_rs::parse("Hello\u{9}".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello\u{b}".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello\u{c}".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello\t".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello ".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello=".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello")),TokenType::Equal]));
+    assert_eq!(js_parser_rs::parse("Hello('sd'".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello")),TokenType::LeftParen,string("sd", "'sd'")]));
+    assert_eq!(js_parser_rs::parse("Hello|Hello".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello")),TokenType::OrBitwise,TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello.Hello".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello")),TokenType::Point,TokenType::Identifier(Cow::from("Hello"))]));
+    assert_eq!(js_parser_rs::parse("Hello/Hello".chars()), Ok(vec![TokenType::Identifier(Cow::from("Hello")),TokenType::Divide,TokenType::Identifier(Cow::from("Hello"))]));
 
-    assert_eq!(js_parser_rs::parse("\\u005f\\u005f\\u0076\\u0061\\u0072".chars()), Ok(vec![TokenType::Identifier(String::from("__var"))]));
+    assert_eq!(js_parser_rs::parse("\\u005f\\u005f\\u0076\\u0061\\u0072".chars()), Ok(vec![TokenType::Identifier(Cow::from("__var"))]));
 }
 
 #[test]
@@ -160,10 +261,8 @@ fn test_keyword() {
     assert_eq!(js_parser_rs::parse("finally".chars()), Ok(vec![TokenType::Finally]));
     assert_eq!(js_parser_rs::parse("super".chars()), Ok(vec![TokenType::Super]));
     assert_eq!(js_parser_rs::parse("with".chars()), Ok(vec![TokenType::With]));
-    assert_eq!(js_parser_rs::parse("yield".chars()), Ok(vec![TokenType::Yield]));
     assert_eq!(js_parser_rs::parse("default".chars()), Ok(vec![TokenType::Default]));
     assert_eq!(js_parser_rs::parse("function".chars()), Ok(vec![TokenType::Function]));
-    assert_eq!(js_parser_rs::parse("of".chars()), Ok(vec![TokenType::Of]));
     assert_eq!(js_parser_rs::parse("in".chars()), Ok(vec![TokenType::In]));
     assert_eq!(js_parser_rs::parse("for".chars()), Ok(vec![TokenType::For]));
     assert_eq!(js_parser_rs::parse("while".chars()), Ok(vec![TokenType::While]));
@@ -171,6 +270,21 @@ fn test_keyword() {
     assert_eq!(js_parser_rs::parse("break".chars()), Ok(vec![TokenType::Break]));
     assert_eq!(js_parser_rs::parse("continue".chars()), Ok(vec![TokenType::Continue]));
     assert_eq!(js_parser_rs::parse("new".chars()), Ok(vec![TokenType::New]));
+    assert_eq!(js_parser_rs::parse("import".chars()), Ok(vec![TokenType::Import]));
+    assert_eq!(js_parser_rs::parse("enum".chars()), Ok(vec![TokenType::Enum]));
+}
+
+#[test]
+fn test_contextual_keyword() {
+    assert_eq!(js_parser_rs::parse("yield".chars()), Ok(vec![TokenType::Contextual(Contextual::Yield)]));
+    assert_eq!(js_parser_rs::parse("of".chars()), Ok(vec![TokenType::Contextual(Contextual::Of)]));
+    assert_eq!(js_parser_rs::parse("let async await static".chars()), Ok(vec![
+        TokenType::Contextual(Contextual::Let),
+        TokenType::Contextual(Contextual::Async),
+        TokenType::Contextual(Contextual::Await),
+        TokenType::Contextual(Contextual::Static)
+    ]));
+    assert_eq!(js_parser_rs::parse("l\\u0065t".chars()), Ok(vec![TokenType::Identifier(Cow::from("let"))]));
 }
 
 #[test]
@@ -178,19 +292,21 @@ fn test_terminate() {
     assert_eq!(js_parser_rs::parse("\n".chars()), Ok(vec![]));
     assert_eq!(js_parser_rs::parse("\r".chars()), Ok(vec![]));
     assert_eq!(js_parser_rs::parse("\n ;".chars()), Ok(vec![TokenType::Semicolon]));
+    assert_eq!(js_parser_rs::parse("\u{2028}\u{2029}".chars()), Ok(vec![]));
+    assert_eq!(js_parser_rs::parse("`a\r\nb\rc`".chars()), Ok(vec![TokenType::NoSubstitutionTemplate(template("a\nb\nc", "a\nb\nc"))]));
 }
 
 #[test]
 fn test_regex() {
-    assert_eq!(js_parser_rs::parse("= /ab+b/g;".chars()), Ok(vec![TokenType::Equal, TokenType::Literal(LiteralType::Regex(String::from("ab+b"), RegexIdentifier::Global)),TokenType::Semicolon]));
-    assert_eq!(js_parser_rs::parse("( /ab+b/g)".chars()), Ok(vec![TokenType::LeftParen, TokenType::Literal(LiteralType::Regex(String::from("ab+b"), RegexIdentifier::Global)),TokenType::RightParen]));
-    assert_eq!(js_parser_rs::parse("= /ab+b/;".chars()), Ok(vec![TokenType::Equal, TokenType::Literal(LiteralType::Regex(String::from("ab+b"), RegexIdentifier::None)),TokenType::Semicolon]));
-    assert_eq!(js_parser_rs::parse(": /ab+b/g;".chars()), Ok(vec![TokenType::Colon, TokenType::Literal(LiteralType::Regex(String::from("ab+b"), RegexIdentifier::Global)),TokenType::Semicolon]));
-    assert_eq!(js_parser_rs::parse(", /ab+b/g;".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(String::from("ab+b"), RegexIdentifier::Global)),TokenType::Semicolon]));
-    assert_eq!(js_parser_rs::parse(", /*a*/ /ab+b/g;".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(String::from("ab+b"), RegexIdentifier::Global)),TokenType::Semicolon]));
-    assert_eq!(js_parser_rs::parse(", /ab\\/b/g".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(String::from("ab/b"), RegexIdentifier::Global))]));
-    assert_eq!(js_parser_rs::parse(", /ab\\\\b/g".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(String::from("ab\\b"), RegexIdentifier::Global))]));
-    assert_eq!(js_parser_rs::parse(", /^h\\d$/i".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(String::from("^h\\d$"), RegexIdentifier::Ignore))]));
+    assert_eq!(js_parser_rs::parse("= /ab+b/g;".chars()), Ok(vec![TokenType::Equal, TokenType::Literal(LiteralType::Regex(Cow::from("ab+b"), Cow::from("g"))),TokenType::Semicolon]));
+    assert_eq!(js_parser_rs::parse("( /ab+b/g)".chars()), Ok(vec![TokenType::LeftParen, TokenType::Literal(LiteralType::Regex(Cow::from("ab+b"), Cow::from("g"))),TokenType::RightParen]));
+    assert_eq!(js_parser_rs::parse("= /ab+b/;".chars()), Ok(vec![TokenType::Equal, TokenType::Literal(LiteralType::Regex(Cow::from("ab+b"), Cow::from(""))),TokenType::Semicolon]));
+    assert_eq!(js_parser_rs::parse(": /ab+b/g;".chars()), Ok(vec![TokenType::Colon, TokenType::Literal(LiteralType::Regex(Cow::from("ab+b"), Cow::from("g"))),TokenType::Semicolon]));
+    assert_eq!(js_parser_rs::parse(", /ab+b/g;".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(Cow::from("ab+b"), Cow::from("g"))),TokenType::Semicolon]));
+    assert_eq!(js_parser_rs::parse(", /*a*/ /ab+b/g;".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(Cow::from("ab+b"), Cow::from("g"))),TokenType::Semicolon]));
+    assert_eq!(js_parser_rs::parse(", /ab\\/b/g".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(Cow::from("ab\\/b"), Cow::from("g")))]));
+    assert_eq!(js_parser_rs::parse(", /ab\\\\b/g".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(Cow::from("ab\\\\b"), Cow::from("g")))]));
+    assert_eq!(js_parser_rs::parse(", /^h\\d$/i".chars()), Ok(vec![TokenType::Comma, TokenType::Literal(LiteralType::Regex(Cow::from("^h\\d$"), Cow::from("i")))]));
 }
 
 #[test]
@@ -203,14 +319,232 @@ fn test_comment() {
     assert_eq!(js_parser_rs::parse("/*Hello * */;".chars()), Ok(vec![TokenType::Semicolon]));
     assert_eq!(js_parser_rs::parse("//Hello \n;".chars()), Ok(vec![TokenType::Semicolon]));
     assert_eq!(js_parser_rs::parse("/*Hello \n;*/".chars()), Ok(vec![]));
-    //assert_eq!(js_parser_rs::parse("// IE \\r a".chars()), Ok(vec![TokenType::CommentLiteral(String::from(" IE \r a"))]));
+    assert_eq!(js_parser_rs::parse("#!/usr/bin/env node\na".chars()), Ok(vec![TokenType::Identifier(Cow::from("a"))]));
+    assert_eq!(js_parser_rs::parse(" #!a".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('#'))));
+    //assert_eq!(js_parser_rs::parse("// IE \\r a".chars()), Ok(vec![TokenType::CommentLiteral(Cow::from(" IE \r a"))]));
+}
+
+
+#[test]
+fn test_html_comment() {
+    let a = TokenType::Identifier(Cow::from("a"));
+    let b = TokenType::Identifier(Cow::from("b"));
+    assert_eq!(js_parser_rs::parse("a <!-- b\nb".chars()), Ok(vec![a.clone(), b.clone()]));
+    assert_eq!(js_parser_rs::parse("--> a\nb /*\n*/ --> a".chars()), Ok(vec![b.clone()]));
+    assert_eq!(js_parser_rs::parse("a-->b".chars()), Ok(vec![a.clone(), TokenType::Decrement, TokenType::GreaterThan, b.clone()]));
+    assert_eq!(js_parser_rs::parse("a<!-b".chars()), Ok(vec![a.clone(), TokenType::SmallThan, TokenType::Invert, TokenType::Minus, b.clone()]));
+
+    let mut state = LexerState::new(Box::new("a <!--b".chars()));
+    state.set_source_type(SourceType::Module);
+    state.parse().unwrap();
+    let tokens: Vec<TokenType> = state.tokens().into_iter().map(|t| t.token).collect();
+    assert_eq!(tokens, vec![a, TokenType::SmallThan, TokenType::Invert, TokenType::Decrement, b]);
+}
+
+#[test]
+fn test_comment_list() {
+    let mut state = LexerState::new(Box::new("#!node\n/* a\n */ x // b\n<!-- c\n--> d".chars()));
+    state.parse().unwrap();
+    let comments: Vec<(CommentKind, String, usize, usize, (u64, u32), (u64, u32))> = state.comments().into_iter().map(|c| {
+        (c.kind, c.text.into_owned(), c.span.start.offset, c.span.end.offset, (c.span.start.line, c.span.start.col), (c.span.end.line, c.span.end.col))
+    }).collect();
+    assert_eq!(comments, vec![
+        (CommentKind::Hashbang, String::from("node"), 0, 6, (1, 1), (1, 7)),
+        (CommentKind::Block, String::from(" a\n "), 7, 15, (2, 1), (3, 4)),
+        (CommentKind::Line, String::from(" b"), 18, 22, (3, 7), (3, 11)),
+        (CommentKind::Html, String::from(" c"), 23, 29, (4, 1), (4, 7)),
+        (CommentKind::Html, String::from(" d"), 30, 35, (5, 1), (5, 6))
+    ]);
+}
+
+fn template(cooked: &'static str, raw: &'static str) -> TemplateString<'static> {
+    TemplateString { cooked: Some(Cow::from(cooked)), raw: Cow::from(raw) }
+}
+
+#[test]
+fn test_template() {
+    assert_eq!(js_parser_rs::parse("`a$b`".chars()), Ok(vec![TokenType::NoSubstitutionTemplate(template("a$b", "a$b"))]));
+    assert_eq!(js_parser_rs::parse("`a\\n\\`$\\{`".chars()), Ok(vec![TokenType::NoSubstitutionTemplate(template("a\n`${", "a\\n\\`$\\{"))]));
+    assert_eq!(js_parser_rs::parse("`a${b}c${ {d: 1} }e`".chars()), Ok(vec![
+        TokenType::TemplateHead(template("a", "a")),
+        TokenType::Identifier(Cow::from("b")),
+        TokenType::TemplateMiddle(template("c", "c")),
+        TokenType::LeftBrace,
+        TokenType::Identifier(Cow::from("d")),
+        TokenType::Colon,
+        TokenType::Literal(LiteralType::Integer(1)),
+        TokenType::RightBrace,
+        TokenType::TemplateTail(template("e", "e"))]));
+    assert_eq!(js_parser_rs::parse("`${`${a}`}`".chars()), Ok(vec![
+        TokenType::TemplateHead(template("", "")),
+        TokenType::TemplateHead(template("", "")),
+        TokenType::Identifier(Cow::from("a")),
+        TokenType::TemplateTail(template("", "")),
+        TokenType::TemplateTail(template("", ""))]));
+    assert_eq!(js_parser_rs::parse("`\\x\\1`".chars()), Ok(vec![TokenType::NoSubstitutionTemplate(TemplateString { cooked: None, raw: Cow::from("\\x\\1") })]));
+    assert_eq!(js_parser_rs::parse("`a${b".chars()), Ok(vec![TokenType::TemplateHead(template("a", "a")), TokenType::Identifier(Cow::from("b"))]));
+    assert_eq!(js_parser_rs::parse("`a".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF)));
+}
+
+#[test]
+fn test_invalid_number() {
+    assert_eq!(js_parser_rs::parse("0o394".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('9'))));
+    assert_eq!(js_parser_rs::parse("0x;".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidNumber)));
+    assert_eq!(js_parser_rs::parse("9223372036854775808".chars()), Ok(vec![TokenType::Literal(LiteralType::Float(9223372036854775808.0))]));
+}
+
+#[test]
+fn test_unexpected_char() {
+    assert_eq!(js_parser_rs::parse("a @ b".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('@'))));
+    assert_eq!(js_parser_rs::parse("#".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('#'))));
+    assert_eq!(js_parser_rs::parse("ab\u{2603}".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('\u{2603}'))));
+}
+
+#[test]
+fn test_invalid_unicode_escape() {
+    assert_eq!(js_parser_rs::parse("\\u0061b".chars()), Ok(vec![TokenType::Identifier(Cow::from("ab"))]));
+    assert_eq!(js_parser_rs::parse("a\\u00".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)));
+    assert_eq!(js_parser_rs::parse("\\x41".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)));
+    assert_eq!(js_parser_rs::parse("\\uZZZZ".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)));
+    assert_eq!(js_parser_rs::parse("\\u{}".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)));
+    assert_eq!(js_parser_rs::parse("\\u{110000}".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)));
+    assert_eq!(js_parser_rs::parse("\\u0031".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)));
+    assert_eq!(js_parser_rs::parse("a\\u002e".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidUnicodeEscape)));
 }
 
+#[test]
+fn test_unicode_identifier() {
+    assert_eq!(js_parser_rs::parse("café π 変数".chars()), Ok(vec![
+        TokenType::Identifier(Cow::from("café")),
+        TokenType::Identifier(Cow::from("π")),
+        TokenType::Identifier(Cow::from("変数"))
+    ]));
+    assert_eq!(js_parser_rs::parse("a\u{200c}b\u{200d}".chars()), Ok(vec![TokenType::Identifier(Cow::from("a\u{200c}b\u{200d}"))]));
+    assert_eq!(js_parser_rs::parse("x\u{300}\u{663}".chars()), Ok(vec![TokenType::Identifier(Cow::from("x\u{300}\u{663}"))]));
+    assert_eq!(js_parser_rs::parse("\\u{63}af\\u{E9} \\u{1d4d0}".chars()), Ok(vec![
+        TokenType::Identifier(Cow::from("café")),
+        TokenType::Identifier(Cow::from("\u{1d4d0}"))
+    ]));
+    assert_eq!(js_parser_rs::parse("v\\u0061r n\\u{75}ll".chars()), Ok(vec![
+        TokenType::Identifier(Cow::from("var")),
+        TokenType::Identifier(Cow::from("null"))
+    ]));
+    assert_eq!(js_parser_rs::parse("\u{200c}".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('\u{200c}'))));
+}
+
+fn regex(pattern: &'static str, flags: &'static str) -> TokenType<'static> {
+    TokenType::Literal(LiteralType::Regex(Cow::from(pattern), Cow::from(flags)))
+}
+
+#[test]
+fn test_regex_class_and_flags() {
+    assert_eq!(js_parser_rs::parse("= /[/]/".chars()), Ok(vec![TokenType::Equal, regex("[/]", "")]));
+    assert_eq!(js_parser_rs::parse("= /[\\]/]+/".chars()), Ok(vec![TokenType::Equal, regex("[\\]/]+", "")]));
+    assert_eq!(js_parser_rs::parse("= /x/gi;".chars()), Ok(vec![TokenType::Equal, regex("x", "gi"), TokenType::Semicolon]));
+    assert_eq!(js_parser_rs::parse("= /x/dgimsuy".chars()), Ok(vec![TokenType::Equal, regex("x", "dgimsuy")]));
+    assert_eq!(js_parser_rs::parse("= /x/v.a".chars()), Ok(vec![TokenType::Equal, regex("x", "v"), TokenType::Point, TokenType::Identifier(Cow::from("a"))]));
+    assert_eq!(js_parser_rs::parse("= /x/gg".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidRegexFlag('g'))));
+    assert_eq!(js_parser_rs::parse("= /x/gq".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidRegexFlag('q'))));
+    assert_eq!(js_parser_rs::parse("= /x/uv".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::InvalidRegexFlag('v'))));
+    assert_eq!(js_parser_rs::parse("return /x/".chars()), Ok(vec![TokenType::Return, regex("x", "")]));
+    assert_eq!(js_parser_rs::parse("a[0] /x/ y".chars()), Ok(vec![
+        TokenType::Identifier(Cow::from("a")), TokenType::LeftBracket, TokenType::Literal(LiteralType::Integer(0)),
+        TokenType::RightBracket, TokenType::Divide, TokenType::Identifier(Cow::from("x")), TokenType::Divide,
+        TokenType::Identifier(Cow::from("y"))]));
+}
+
+#[test]
+fn test_unterminated_regex() {
+    assert_eq!(js_parser_rs::parse("= /ab".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedRegex)));
+    assert_eq!(js_parser_rs::parse("= /a\nb/".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedRegex)));
+    assert_eq!(js_parser_rs::parse("= /[a/".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedRegex)));
+    assert_eq!(js_parser_rs::parse("= /a\\".chars()), Err(ErrorType::SyntaxError(SyntaxErrorType::UnterminatedRegex)));
+    assert_eq!(js_parser_rs::parse("= /ab/".chars()), Ok(vec![TokenType::Equal, TokenType::Literal(LiteralType::Regex(Cow::from("ab"), Cow::from("")))]));
+}
+
+#[test]
+fn test_error_position() {
+    let mut state = LexerState::new(Box::new("a\n  @".chars()));
+    let err = state.parse().unwrap_err();
+    assert_eq!((err.line(), err.col()), (2, 3));
+}
+
+#[test]
+fn test_token_iterator() {
+    // the input never ends, only the tokens asked for are lexed
+    let input = "import a; ".chars().chain(std::iter::repeat('x'));
+    let tokens: Vec<TokenType> = LexerState::new(Box::new(input)).take(3).map(|t| t.unwrap().token).collect();
+    assert_eq!(tokens, vec![TokenType::Import, TokenType::Identifier(Cow::from("a")), TokenType::Semicolon]);
+
+    let mut state = LexerState::new(Box::new("a / b @ c".chars()));
+    assert_eq!(state.next().map(|t| t.unwrap().token), Some(TokenType::Identifier(Cow::from("a"))));
+    assert_eq!(state.next().map(|t| t.unwrap().token), Some(TokenType::Divide));
+    assert_eq!(state.next().map(|t| t.unwrap().token), Some(TokenType::Identifier(Cow::from("b"))));
+    assert_eq!(state.next().map(|t| t.unwrap_err().error_type), Some(ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('@'))));
+    assert!(state.next().is_none());
+}
+
+/// Whether `text` points into `source` instead of a copy.
+fn borrowed(source: &str, text: &str) -> bool {
+    let start = source.as_ptr() as usize;
+    let ptr = text.as_ptr() as usize;
+    ptr >= start && ptr + text.len() <= start + source.len()
+}
+
+#[test]
+fn test_borrowed_text() {
+    let source = "a \\u0062 'c' \"\\x64\" `e${f}\\n` = /g/i #h 1n";
+    let borrowed = |text: &str| borrowed(source, text);
+    let tokens: Vec<TokenType> = LexerState::from_source(source).map(|t| t.unwrap().token).collect();
+    match tokens.as_slice() {
+        [TokenType::Identifier(a), TokenType::Identifier(b),
+         TokenType::Literal(LiteralType::String(c, c_raw)), TokenType::Literal(LiteralType::String(d, d_raw)),
+         TokenType::TemplateHead(e), TokenType::Identifier(_), TokenType::TemplateTail(n), TokenType::Equal,
+         TokenType::Literal(LiteralType::Regex(g, i)), TokenType::PrivateName(h), TokenType::Literal(LiteralType::BigInt(_))] => {
+            assert!(borrowed(a) && !borrowed(b));
+            assert!(borrowed(c) && borrowed(c_raw) && c_raw == "'c'");
+            assert!(!borrowed(d) && borrowed(d_raw) && d == "d");
+            assert!(borrowed(e.cooked.as_ref().unwrap()) && borrowed(&e.raw));
+            assert!(!borrowed(n.cooked.as_ref().unwrap()) && borrowed(&n.raw));
+            assert!(borrowed(g) && borrowed(i) && g == "g" && i == "i");
+            assert!(borrowed(h) && h == "h");
+        }
+        _ => panic!("unexpected tokens {:?}", tokens)
+    }
+
+    let mut state = LexerState::from_source("x = 'y'");
+    let tokens: Vec<Token> = state.by_ref().map(|t| t.unwrap()).collect();
+    assert_eq!(state.source_text(tokens[2].span), Some("'y'"));
+}
 
 #[test]
-#[should_panic]
-fn sould_panic_number() {
-    println!("{:?}", js_parser_rs::parse("0o394".chars()));
+fn test_borrowed_comments_and_rescan() {
+    let source = "/** a */ // b\n/* \\u0063 */";
+    let mut state = LexerState::from_source(source);
+    state.parse().unwrap();
+    let comments = state.comments();
+    assert_eq!(comments.iter().map(|c| c.text.as_ref()).collect::<Vec<_>>(), vec!["* a ", " b", " \\u0063 "]);
+    assert!(comments.iter().all(|c| borrowed(source, &c.text)));
+
+    // comments can be drained while tokens stream
+    let mut state = LexerState::from_source("a /* b */ c // d\ne");
+    let mut drained = vec![];
+    while let Some(token) = state.next() {
+        token.unwrap();
+        drained.extend(state.take_comments().into_iter().map(|c| c.text.into_owned()));
+    }
+    assert_eq!(drained, vec![" b ", " d"]);
+    assert!(state.comments().is_empty());
+
+    let source = "/=b/g";
+    let mut state = LexerState::from_source(source);
+    let slash = state.next_token(Goal::Div).unwrap().unwrap();
+    match state.rescan_regex(slash).unwrap().token {
+        TokenType::Literal(LiteralType::Regex(ref pattern, ref flags)) => {
+            assert!(borrowed(source, pattern) && pattern == "=b" && flags == "g");
+        }
+        ref token => panic!("unexpected {:?}", token)
+    }
 }
 
 #[test]
@@ -265,3 +599,69 @@ impl Iterator for OwningChars {
 //fn some_code() {
 //    assert_eq!(js_parser_rs::parse("var y = 2 +3; // defines the variable y and assigns to it the value 2\n while(y< 10) {\n y++;\n}\n console.log(y)".chars()), Ok(vec![]));
 //}
+
+fn spans(s: &'static str) -> Vec<(TokenType, usize, usize, (u64, u32), (u64, u32))> {
+    let mut state = LexerState::new(Box::new(s.chars()));
+    state.parse().unwrap();
+    state.tokens().into_iter().map(|t| {
+        let span = t.span;
+        (t.token, span.start.offset, span.end.offset, (span.start.line, span.start.col), (span.end.line, span.end.col))
+    }).collect()
+}
+
+#[test]
+fn test_token_spans() {
+    assert_eq!(spans("var ab = 1.5;"), vec![
+        (TokenType::Var, 0, 3, (1, 1), (1, 4)),
+        (TokenType::Identifier(Cow::from("ab")), 4, 6, (1, 5), (1, 7)),
+        (TokenType::Equal, 7, 8, (1, 8), (1, 9)),
+        (TokenType::Literal(LiteralType::Float(1.5)), 9, 12, (1, 10), (1, 13)),
+        (TokenType::Semicolon, 12, 13, (1, 13), (1, 14))
+    ]);
+    assert_eq!(spans("a >>= b..c"), vec![
+        (TokenType::Identifier(Cow::from("a")), 0, 1, (1, 1), (1, 2)),
+        (TokenType::RightShiftAssign, 2, 5, (1, 3), (1, 6)),
+        (TokenType::Identifier(Cow::from("b")), 6, 7, (1, 7), (1, 8)),
+        (TokenType::Point, 7, 8, (1, 8), (1, 9)),
+        (TokenType::Point, 8, 9, (1, 9), (1, 10)),
+        (TokenType::Identifier(Cow::from("c")), 9, 10, (1, 10), (1, 11))
+    ]);
+    assert_eq!(spans("(/ab/g)"), vec![
+        (TokenType::LeftParen, 0, 1, (1, 1), (1, 2)),
+        (TokenType::Literal(LiteralType::Regex(Cow::from("ab"), Cow::from("g"))), 1, 6, (1, 2), (1, 7)),
+        (TokenType::RightParen, 6, 7, (1, 7), (1, 8))
+    ]);
+}
+
+#[test]
+fn test_line_terminator_spans() {
+    assert_eq!(spans("a\r\nb\u{2028}c // d\r\ne"), vec![
+        (TokenType::Identifier(Cow::from("a")), 0, 1, (1, 1), (1, 2)),
+        (TokenType::Identifier(Cow::from("b")), 3, 4, (2, 1), (2, 2)),
+        (TokenType::Identifier(Cow::from("c")), 7, 8, (3, 1), (3, 2)),
+        (TokenType::Identifier(Cow::from("e")), 15, 16, (4, 1), (4, 2))
+    ]);
+    assert_eq!(spans("\u{feff}a"), vec![(TokenType::Identifier(Cow::from("a")), 3, 4, (1, 1), (1, 2))]);
+}
+
+#[test]
+fn test_newline_before() {
+    let newlines = |s: &'static str| -> Vec<bool> {
+        let mut state = LexerState::new(Box::new(s.chars()));
+        state.parse().unwrap();
+        state.tokens().into_iter().map(|t| t.newline_before).collect()
+    };
+    assert_eq!(newlines("a\nb c\r\n\r\nd"), vec![false, true, false, true]);
+    assert_eq!(newlines("\na /*\n*/ b /* */ c // d\ne"), vec![true, true, false, true]);
+    assert_eq!(newlines("a\u{2028}b\u{2029}c"), vec![false, true, true]);
+}
+
+#[test]
+fn test_multi_line_token_spans() {
+    assert_eq!(spans("'\u{e9}\\\nx';\n/* a\n b */ c // d\ne"), vec![
+        (string("\u{e9}x", "'\u{e9}\\\nx'"), 0, 7, (1, 1), (2, 3)),
+        (TokenType::Semicolon, 7, 8, (2, 3), (2, 4)),
+        (TokenType::Identifier(Cow::from("c")), 20, 21, (4, 7), (4, 8)),
+        (TokenType::Identifier(Cow::from("e")), 27, 28, (5, 1), (5, 2))
+    ]);
+}
@@ -0,0 +1,889 @@
+extern crate js_parser_rs;
+
+use js_parser_rs::JsContext;
+use js_parser_rs::ast::node::*;
+use js_parser_rs::lexer::enums::LiteralType;
+use js_parser_rs::lexer::token::{Span, CommentKind};
+use js_parser_rs::lexer::enums::{TokenType, Contextual, SourceType};
+use js_parser_rs::lexer::state::LexerState;
+use js_parser_rs::scope::parser::Parser;
+use js_parser_rs::error::error::{Error, ErrorType, SyntaxErrorType};
+use std::borrow::Cow;
+
+fn parse(s: &str) -> Result<Program, Error> {
+    JsContext::new().parse(String::from(s))
+}
+
+fn error(s: &str) -> Error {
+    parse(s).unwrap_err()
+}
+
+fn syntax_error(s: &str) -> SyntaxErrorType {
+    match error(s).error_type {
+        ErrorType::SyntaxError(t) => t,
+        t => panic!("unexpected {:?}", t)
+    }
+}
+
+fn body(s: &str) -> Vec<Node<Statement>> {
+    parse(s).unwrap().body
+}
+
+fn node<T>(item: T) -> Node<T> {
+    Node::new(Span::default(), item)
+}
+
+fn name(name: &str) -> Node<String> {
+    node(String::from(name))
+}
+
+fn ident(name: &str) -> Node<Expression> {
+    node(Expression::Identifier(String::from(name)))
+}
+
+fn binding(name: &str) -> Node<Pattern> {
+    node(Pattern::Identifier(String::from(name)))
+}
+
+fn int(i: i64) -> Node<Expression> {
+    node(Expression::Literal(LiteralType::Integer(i)))
+}
+
+fn expr_stmt(expr: Expression) -> Node<Statement> {
+    node(Statement::Expression(node(expr)))
+}
+
+#[test]
+fn test_empty_program() {
+    assert_eq!(parse(""), Ok(Program { body: vec![], comments: vec![], source_type: SourceType::Script }));
+    assert_eq!(body(";"), vec![node(Statement::Empty)]);
+}
+
+#[test]
+fn test_program_comments() {
+    let program = parse("/** doc */ a; // b").unwrap();
+    assert_eq!(program.body, vec![expr_stmt(Expression::Identifier(String::from("a")))]);
+    let comments: Vec<(CommentKind, String)> = program.comments.into_iter().map(|c| (c.kind, c.text.into_owned())).collect();
+    assert_eq!(comments, vec![(CommentKind::Block, String::from("* doc ")), (CommentKind::Line, String::from(" b"))]);
+}
+
+#[test]
+fn test_binary_precedence() {
+    assert_eq!(body("a + b * c;"), vec![expr_stmt(Expression::Binary {
+        operator: BinaryOperator::Plus,
+        left: Box::new(ident("a")),
+        right: Box::new(node(Expression::Binary {
+            operator: BinaryOperator::Multiple,
+            left: Box::new(ident("b")),
+            right: Box::new(ident("c"))
+        }))
+    })]);
+    assert_eq!(body("a - b - c"), vec![expr_stmt(Expression::Binary {
+        operator: BinaryOperator::Minus,
+        left: Box::new(node(Expression::Binary {
+            operator: BinaryOperator::Minus,
+            left: Box::new(ident("a")),
+            right: Box::new(ident("b"))
+        })),
+        right: Box::new(ident("c"))
+    })]);
+    assert_eq!(body("a || b && c"), vec![expr_stmt(Expression::Logical {
+        operator: LogicalOperator::Or,
+        left: Box::new(ident("a")),
+        right: Box::new(node(Expression::Logical {
+            operator: LogicalOperator::And,
+            left: Box::new(ident("b")),
+            right: Box::new(ident("c"))
+        }))
+    })]);
+
+    // a unary operand on the left of `**` has to be parenthesized
+    let exp = SyntaxErrorType::Unexpected(TokenType::Exp);
+    assert_eq!(syntax_error("-a ** b"), exp);
+    assert_eq!(syntax_error("typeof a ** 2"), exp);
+    assert_eq!(syntax_error("a * -b ** c"), exp);
+    assert_eq!(syntax_error("async function f() { await a ** b }"), exp);
+    assert!(parse("(-a) ** b; a ** -b; (typeof a) ** 2; -(a ** b); a++ ** 2").is_ok());
+}
+
+#[test]
+fn test_variable_declaration() {
+    assert_eq!(body("var a = 1, b;"), vec![node(Statement::Declaration(Declaration::Variable(VariableDeclaration {
+        kind: VariableKind::Var,
+        declarations: vec![
+            node(VariableDeclarator { id: binding("a"), init: Some(int(1)) }),
+            node(VariableDeclarator { id: binding("b"), init: None })
+        ]
+    })))]);
+    assert_eq!(body("var [a, , ...b] = c"), vec![node(Statement::Declaration(Declaration::Variable(VariableDeclaration {
+        kind: VariableKind::Var,
+        declarations: vec![node(VariableDeclarator {
+            id: node(Pattern::Array(vec![
+                Some(binding("a")),
+                None,
+                Some(node(Pattern::Rest(Box::new(binding("b")))))
+            ])),
+            init: Some(ident("c"))
+        })]
+    })))]);
+}
+
+#[test]
+fn test_member_and_call() {
+    assert_eq!(body("a.b(c)[d]"), vec![expr_stmt(Expression::Member {
+        object: Box::new(node(Expression::Call {
+            callee: Box::new(node(Expression::Member {
+                object: Box::new(ident("a")),
+                property: Box::new(ident("b")),
+                computed: false
+            })),
+            arguments: vec![ident("c")]
+        })),
+        property: Box::new(ident("d")),
+        computed: true
+    })]);
+    assert_eq!(body("new A"), vec![expr_stmt(Expression::New {
+        callee: Box::new(ident("A")),
+        arguments: vec![]
+    })]);
+}
+
+fn quasi(cooked: &str, tail: bool) -> Node<TemplateElement> {
+    node(TemplateElement { cooked: Some(String::from(cooked)), raw: String::from(cooked), tail: tail })
+}
+
+#[test]
+fn test_template() {
+    assert_eq!(body("`a${b}c${d}`"), vec![expr_stmt(Expression::Template(TemplateLiteral {
+        quasis: vec![quasi("a", false), quasi("c", false), quasi("", true)],
+        expressions: vec![ident("b"), ident("d")]
+    }))]);
+    assert_eq!(body("f.g`a`()"), vec![expr_stmt(Expression::Call {
+        callee: Box::new(node(Expression::TaggedTemplate {
+            tag: Box::new(node(Expression::Member {
+                object: Box::new(ident("f")),
+                property: Box::new(ident("g")),
+                computed: false
+            })),
+            quasi: node(TemplateLiteral { quasis: vec![quasi("a", true)], expressions: vec![] })
+        })),
+        arguments: vec![]
+    })]);
+    assert_eq!(syntax_error("`a${b c}`"), SyntaxErrorType::Unexpected(TokenType::Identifier(Cow::from("c"))));
+    assert_eq!(error("`a${b c}`").expected(), &["`}`"]);
+    assert_eq!(syntax_error("`\\unicode`"), SyntaxErrorType::InvalidEscape);
+    match body("f`\\unicode`")[0].item {
+        Statement::Expression(Node { item: Expression::TaggedTemplate { ref quasi, .. }, .. }) => {
+            assert_eq!(quasi.item.quasis[0].item.cooked, None);
+            assert_eq!(quasi.item.quasis[0].item.raw, "\\unicode");
+        }
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+}
+
+fn regex(pattern: &'static str, flags: &'static str) -> Node<Expression> {
+    node(Expression::Literal(LiteralType::Regex(Cow::from(pattern), Cow::from(flags))))
+}
+
+#[test]
+fn test_regex_goal() {
+    assert_eq!(body("function f() { return /x/ }")[0], node(Statement::Declaration(Declaration::Function(Function {
+        id: Some(name("f")),
+        params: vec![],
+        body: node(vec![node(Statement::Return(Some(regex("x", ""))))]),
+        generator: false,
+        is_async: false
+    }))));
+    assert_eq!(body("[/a/]"), vec![expr_stmt(Expression::Array(vec![Some(regex("a", ""))]))]);
+    assert_eq!(body("a && /b/g"), vec![expr_stmt(Expression::Logical {
+        operator: LogicalOperator::And,
+        left: Box::new(ident("a")),
+        right: Box::new(regex("b", "g"))
+    })]);
+    assert_eq!(body("{ /re/ }"), vec![node(Statement::Block(vec![expr_stmt(regex("re", "").item)]))]);
+    assert_eq!(body(";/re/"), vec![node(Statement::Empty), expr_stmt(regex("re", "").item)]);
+    assert_eq!(body("/=a/"), vec![expr_stmt(regex("=a", "").item)]);
+    match body("!/a/.test(s)")[0].item {
+        Statement::Expression(Node { item: Expression::Unary { operator: UnaryOperator::Invert, .. }, .. }) => (),
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+    assert_eq!(body("a / b / c"), vec![expr_stmt(Expression::Binary {
+        operator: BinaryOperator::Divide,
+        left: Box::new(node(Expression::Binary {
+            operator: BinaryOperator::Divide,
+            left: Box::new(ident("a")),
+            right: Box::new(ident("b"))
+        })),
+        right: Box::new(ident("c"))
+    })]);
+    assert_eq!(syntax_error("a = /x"), SyntaxErrorType::UnterminatedRegex);
+}
+
+#[test]
+fn test_function() {
+    assert_eq!(body("function f(a, b = 1) { return a }"), vec![node(Statement::Declaration(Declaration::Function(Function {
+        id: Some(name("f")),
+        params: vec![
+            binding("a"),
+            node(Pattern::Assignment {
+                left: Box::new(binding("b")),
+                right: Box::new(int(1))
+            })
+        ],
+        body: node(vec![node(Statement::Return(Some(ident("a"))))]),
+        generator: false,
+        is_async: false
+    })))]);
+    assert_eq!(body("a => a"), vec![expr_stmt(Expression::Arrow {
+        params: vec![binding("a")],
+        body: ArrowBody::Expression(Box::new(ident("a"))),
+        is_async: false
+    })]);
+}
+
+#[test]
+fn test_contextual_keywords() {
+    assert!(parse("var target = obj.get(x); function set(v) {} of = let + async + static").is_ok());
+    assert!(parse("({ get: 1, set() {}, get a() {}, of })").is_ok());
+    assert!(parse("({ get a() {}, set a({ b }) {}, set [c](d = 1) {} })").is_ok());
+    for source in &["({ get a(b) {} })", "({ set a() {} })", "({ set a(b, c) {} })", "({ set a(...b) {} })"] {
+        assert_eq!(syntax_error(source), SyntaxErrorType::InvalidAccessor, "{}", source);
+    }
+    assert!(parse("function f() { return new.target }").is_ok());
+    assert_eq!(body("yield"), vec![expr_stmt(Expression::Identifier(String::from("yield")))]);
+    assert_eq!(body("function* g() { yield }"), vec![node(Statement::Declaration(Declaration::Function(Function {
+        id: Some(name("g")),
+        params: vec![],
+        body: node(vec![expr_stmt(Expression::Yield { argument: None, delegate: false })]),
+        generator: true,
+        is_async: false
+    })))]);
+    assert!(parse("await = 1").is_ok());
+    assert!(parse("function f() { 'use strict' } let = 1").is_ok());
+}
+
+#[test]
+fn test_reserved_words() {
+    assert_eq!(syntax_error("v\\u0061r = 1"), SyntaxErrorType::ReservedWord(String::from("var")));
+    assert_eq!(syntax_error("function f() { new.t\\u0061rget }"), SyntaxErrorType::Unexpected(TokenType::Identifier(Cow::from("target"))));
+    assert_eq!(syntax_error("'use strict'; var let"), SyntaxErrorType::ReservedWord(String::from("let")));
+    assert_eq!(syntax_error("function f() { \"use strict\"; yield }"), SyntaxErrorType::ReservedWord(String::from("yield")));
+    assert_eq!(syntax_error("function* g() { var yield }"), SyntaxErrorType::ReservedWord(String::from("yield")));
+    assert_eq!(syntax_error("'use strict'; ({ implements })"), SyntaxErrorType::ReservedWord(String::from("implements")));
+    let err = JsContext::new().parse_module(String::from("var await")).unwrap_err();
+    assert_eq!(err.error_type, ErrorType::SyntaxError(SyntaxErrorType::ReservedWord(String::from("await"))));
+    assert!(JsContext::new().parse_module(String::from("var static")).is_err());
+
+    // a string continued on the next line is not a directive
+    assert!(parse("'use strict'\n+ 1; var static").is_ok());
+    assert!(parse("'use strict'\n.length; var static").is_ok());
+    assert!(parse("function f() { 'a'\n('use strict'); var static }").is_ok());
+    assert_eq!(syntax_error("'use strict'\nvar static"), SyntaxErrorType::ReservedWord(String::from("static")));
+    assert_eq!(syntax_error("'a'\n'use strict'\n!function () { var static }"), SyntaxErrorType::ReservedWord(String::from("static")));
+}
+
+#[test]
+fn test_legacy_octal() {
+    assert!(parse("017; 08; 09.5; 0; 0.5").is_ok());
+    let octal = SyntaxErrorType::StrictMode("legacy octal literal");
+    assert_eq!(syntax_error("'use strict'; 017"), octal);
+    assert_eq!(syntax_error("'use strict'; 08"), octal);
+    assert_eq!(syntax_error("function f() { 'use strict'; return 09.5 }"), octal);
+    assert!(parse("function f() { 'use strict' } 017").is_ok());
+    let err = JsContext::new().parse_module(String::from("x = 017")).unwrap_err();
+    assert_eq!(err.error_type, ErrorType::SyntaxError(octal));
+    assert!(JsContext::new().parse_module(String::from("0; 0.5; 0o17")).is_ok());
+
+    let escape = SyntaxErrorType::StrictMode("octal escape sequence");
+    assert!(parse("'\\017'; '\\8'; '\\08'").is_ok());
+    assert_eq!(syntax_error("'use strict'; '\\017'"), escape);
+    assert_eq!(syntax_error("'use strict'; '\\8'"), escape);
+    assert_eq!(syntax_error("function f() { '\\07'; 'use strict' }"), escape);
+    assert!(parse("'use strict'; '\\0'; '\\\\017'").is_ok());
+    let err = JsContext::new().parse_module(String::from("x = '\\9'")).unwrap_err();
+    assert_eq!(err.error_type, ErrorType::SyntaxError(escape));
+}
+
+#[test]
+fn test_statements() {
+    assert_eq!(body("if (a) b; else c"), vec![node(Statement::If {
+        test: ident("a"),
+        consequent: Box::new(node(Statement::Expression(ident("b")))),
+        alternate: Some(Box::new(node(Statement::Expression(ident("c")))))
+    })]);
+    assert_eq!(body("while (a) {}"), vec![node(Statement::While {
+        test: ident("a"),
+        body: Box::new(node(Statement::Block(vec![])))
+    })]);
+    assert_eq!(body("try {} catch (e) {}"), vec![node(Statement::Try {
+        block: node(vec![]),
+        handler: Some(node(CatchClause { param: binding("e"), body: node(vec![]) })),
+        finalizer: None
+    })]);
+}
+
+#[test]
+fn test_strict_mode() {
+    let with = SyntaxErrorType::StrictMode("`with` statement");
+    assert!(parse("with (a) b").is_ok());
+    assert_eq!(syntax_error("'use strict'; with (a) b"), with);
+    assert_eq!(syntax_error("function f() { 'use strict'; with (a) b }"), with);
+    let err = JsContext::new().parse_module(String::from("with (a) b")).unwrap_err();
+    assert_eq!(err.error_type, ErrorType::SyntaxError(with));
+
+    let duplicate = SyntaxErrorType::DuplicateParameter(String::from("a"));
+    assert!(parse("function f(a, a) {} (function (a, b, a) {})").is_ok());
+    assert_eq!(syntax_error("function f(a, a) { 'use strict' }"), duplicate);
+    assert_eq!(syntax_error("function f(a, [a]) {}"), duplicate);
+    assert_eq!(syntax_error("(a, a) => a"), duplicate);
+    assert_eq!(syntax_error("({ m(a, a) {} })"), duplicate);
+}
+
+#[test]
+fn test_invalid_assignment() {
+    assert_eq!(syntax_error("1 = a"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("try {}"), SyntaxErrorType::UnexpectedEOF);
+
+    // `{ a = 1 }` only as a pattern
+    assert!(parse("({ a = 1 } = b); [{ a = 1 }] = b; ({ a = 1 }) => 0; async ({ a = 1 }) => 0; for ({ a = 1 } of b) ;").is_ok());
+    let equal = SyntaxErrorType::Unexpected(TokenType::Equal);
+    assert_eq!(syntax_error("x = { a = 1 }"), equal);
+    assert_eq!(syntax_error("({ a = 1 })"), equal);
+    assert_eq!(syntax_error("f({ a = 1 })"), equal);
+    assert_eq!(syntax_error("[f({ a = 1 })] = x"), equal);
+    assert_eq!(syntax_error("({ a = 1 }).b = 1"), equal);
+    assert_eq!(syntax_error("({ b: { a = 1 } }.c)"), equal);
+    assert_eq!(syntax_error("for ({ a = 1 }; ;) ;"), equal);
+    assert_eq!(error("x = { a = 1 }").col(), 9);
+
+    // only identifiers and members may be parenthesized in a pattern
+    assert!(parse("(a) = 1; ((a).b) = 1; [(a), (b.c)] = 1; ({ a: (b) } = c); [(a, b).c] = 1; [({}).x] = 1").is_ok());
+    assert_eq!(syntax_error("({ a }) = 1"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("([a]) = 1"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("[({ a })] = 1"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("(a = 1) = 2"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("for (([a]) of b) ;"), SyntaxErrorType::InvalidAssignmentTarget);
+
+    // a rest element comes last and has no initializer
+    assert!(parse("[a, ...b] = c; [...[a, b]] = c; async (a, ...b) => 0").is_ok());
+    assert_eq!(syntax_error("[...a, b] = c"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("[...a = 1] = c"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("for ([...a, b] of c) ;"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("async (...a, b) => 0"), SyntaxErrorType::InvalidAssignmentTarget);
+
+    // the parameters of an arrow function only bind names
+    assert!(parse("([a], { b: c }, d = (e)) => 1; async ([a], { b: [c] }) => 1").is_ok());
+    for source in &["(a.b) => 1", "async (a.b) => 1", "([a[0]]) => 1", "({ a: b.c }) => 1", "((a)) => 1",
+                    "([(a)]) => 1", "({ a: (b) }) => 1", "async ((a)) => 1"] {
+        assert_eq!(syntax_error(source), SyntaxErrorType::InvalidAssignmentTarget, "{}", source);
+    }
+
+    // nor do they yield or await
+    let yield_expr = SyntaxErrorType::ParameterExpression("yield");
+    let await_expr = SyntaxErrorType::ParameterExpression("await");
+    assert!(parse("function* g() { (x = yield); (x = function* () { yield }) => 1 }").is_ok());
+    assert!(parse("async function f() { (x = async () => await 1) => 1; async (x = function () {}) => 1 }").is_ok());
+    assert_eq!(syntax_error("function* g() { (x = yield) => 1 }"), yield_expr);
+    assert_eq!(syntax_error("function* g() { function* h(x = yield) {} }"), yield_expr);
+    assert_eq!(syntax_error("async function f() { (x = await 1) => 1 }"), await_expr);
+    assert_eq!(syntax_error("async function f() { async (x = [await 1]) => 1 }"), await_expr);
+    assert_eq!(syntax_error("async function f(x = await 1) {}"), await_expr);
+
+    // `++` and `--` only update identifiers and members
+    assert!(parse("++a; a--; --a.b; a[0]++; (a)++; ++(a.b); a\n++b").is_ok());
+    assert_eq!(syntax_error("++1"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("1++"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("--this"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("a()++"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("++a++"), SyntaxErrorType::InvalidAssignmentTarget);
+}
+
+#[test]
+fn test_error_position() {
+    let err = error("if (a;\n");
+    assert_eq!(err.error_type, ErrorType::SyntaxError(SyntaxErrorType::Unexpected(TokenType::Semicolon)));
+    assert_eq!((err.line(), err.col()), (1, 6));
+    assert_eq!(err.expected(), &["`)`"]);
+    assert_eq!(err.to_string(), "expected `)` but found `;` at 1:6");
+
+    let err = error("a;\n  [b] += 1");
+    assert_eq!(err.error_type, ErrorType::SyntaxError(SyntaxErrorType::InvalidAssignmentTarget));
+    assert_eq!((err.line(), err.col()), (2, 3));
+    assert_eq!((err.span().start.offset, err.span().end.offset), (5, 8));
+    assert_eq!(err.to_string(), "invalid assignment target at 2:3");
+
+    let err = error("f(a;");
+    assert_eq!(err.expected(), &["`,`", "`)`"]);
+    assert_eq!(err.to_string(), "expected `,` or `)` but found `;` at 1:4");
+    assert_eq!(error("const a;").to_string(), "declaration without initializer at 1:7");
+    assert_eq!(error("'use strict'; with (a) b").to_string(), "`with` statement in strict mode at 1:15");
+
+    let err = error("try {} x");
+    assert_eq!(err.expected(), &["`catch`", "`finally`"]);
+    assert_eq!(err.to_string(), "expected `catch` or `finally` but found identifier at 1:8");
+
+    let err = error("var a = ");
+    assert_eq!(err.error_type, ErrorType::SyntaxError(SyntaxErrorType::UnexpectedEOF));
+    assert_eq!(err.expected(), &["expression"]);
+    assert_eq!((err.line(), err.col()), (1, 8));
+}
+
+/// Source range of the expression in the first statement of `s`.
+fn expr_range(s: &str) -> (usize, usize) {
+    match body(s)[0].item {
+        Statement::Expression(ref expr) => (expr.span.start.offset, expr.span.end.offset),
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+}
+
+#[test]
+fn test_parenthesized_spans() {
+    // an expression starts at the parenthesis around its first operand, like in acorn
+    assert_eq!(expr_range("(a) + b"), (0, 7));
+    assert_eq!(expr_range("(a).b"), (0, 5));
+    assert_eq!(expr_range("(a)()"), (0, 5));
+    assert_eq!(expr_range("(a)`b`"), (0, 6));
+    assert_eq!(expr_range("(a) = 1"), (0, 7));
+    assert_eq!(expr_range("((a)) ? b : c"), (0, 13));
+    assert_eq!(expr_range("(a)++"), (0, 5));
+    assert_eq!(expr_range("(a), b"), (0, 6));
+    assert_eq!(expr_range("((a), b)"), (1, 7));
+    match body("a * (b) ** c")[0].item {
+        Statement::Expression(Node { item: Expression::Binary { ref right, .. }, .. }) => {
+            assert_eq!((right.span.start.offset, right.span.end.offset), (4, 12));
+        }
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+}
+
+#[test]
+fn test_streaming() {
+    // the parser stops at the first bad token, without lexing the rest of the input
+    let input = "a b".chars().chain(std::iter::repeat(';'));
+    let err = Parser::from_lexer(LexerState::new(Box::new(input))).unwrap_err();
+    assert_eq!(err.error_type, ErrorType::SyntaxError(SyntaxErrorType::Unexpected(TokenType::Identifier(Cow::from("b")))));
+
+    // lexer errors surface where the parser reaches them
+    let err = error("a;\nb @ c d");
+    assert_eq!(err.error_type, ErrorType::SyntaxError(SyntaxErrorType::UnexpectedChar('@')));
+    assert_eq!((err.line(), err.col()), (2, 3));
+}
+
+#[test]
+fn test_automatic_semicolon_insertion() {
+    assert_eq!(body("a\n(b)"), vec![expr_stmt(Expression::Call {
+        callee: Box::new(ident("a")),
+        arguments: vec![ident("b")]
+    })]);
+    assert_eq!(body("a\nb"), vec![expr_stmt(Expression::Identifier(String::from("a"))), expr_stmt(Expression::Identifier(String::from("b")))]);
+    assert_eq!(body("a\n++b"), vec![
+        expr_stmt(Expression::Identifier(String::from("a"))),
+        expr_stmt(Expression::Update { operator: UpdateOperator::Increment, prefix: true, argument: Box::new(ident("b")) })
+    ]);
+    assert_eq!(body("function f() { return\na }")[0], node(Statement::Declaration(Declaration::Function(Function {
+        id: Some(name("f")),
+        params: vec![],
+        body: node(vec![node(Statement::Return(None)), expr_stmt(Expression::Identifier(String::from("a")))]),
+        generator: false,
+        is_async: false
+    }))));
+    assert_eq!(body("function* g() { yield\na }")[0], node(Statement::Declaration(Declaration::Function(Function {
+        id: Some(name("g")),
+        params: vec![],
+        body: node(vec![
+            expr_stmt(Expression::Yield { argument: None, delegate: false }),
+            expr_stmt(Expression::Identifier(String::from("a")))
+        ]),
+        generator: true,
+        is_async: false
+    }))));
+    assert_eq!(body("a: { break\na }"), vec![node(Statement::Labeled {
+        label: name("a"),
+        body: Box::new(node(Statement::Block(vec![node(Statement::Break(None)), expr_stmt(Expression::Identifier(String::from("a")))])))
+    })]);
+    assert_eq!(body("if (a) b\nelse c").len(), 1);
+
+    assert_eq!(syntax_error("a b"), SyntaxErrorType::Unexpected(TokenType::Identifier(Cow::from("b"))));
+    assert_eq!(syntax_error("throw\na"), SyntaxErrorType::UnexpectedEOL);
+    assert_eq!(syntax_error("a\n=> a"), SyntaxErrorType::UnexpectedEOL);
+    assert_eq!(syntax_error("(a)\n=> a"), SyntaxErrorType::UnexpectedEOL);
+}
+
+#[test]
+fn test_for_statements() {
+    assert_eq!(body("for (;;) ;"), vec![node(Statement::For {
+        init: None,
+        test: None,
+        update: None,
+        body: Box::new(node(Statement::Empty))
+    })]);
+    assert_eq!(body("for (var i = 0, n = a.length; i < n; i++) ;"), vec![node(Statement::For {
+        init: Some(ForInit::Variable(node(VariableDeclaration {
+            kind: VariableKind::Var,
+            declarations: vec![
+                node(VariableDeclarator { id: binding("i"), init: Some(int(0)) }),
+                node(VariableDeclarator {
+                    id: binding("n"),
+                    init: Some(node(Expression::Member {
+                        object: Box::new(ident("a")),
+                        property: Box::new(ident("length")),
+                        computed: false
+                    }))
+                })
+            ]
+        }))),
+        test: Some(node(Expression::Binary {
+            operator: BinaryOperator::SmallThan,
+            left: Box::new(ident("i")),
+            right: Box::new(ident("n"))
+        })),
+        update: Some(node(Expression::Update {
+            operator: UpdateOperator::Increment,
+            prefix: false,
+            argument: Box::new(ident("i"))
+        })),
+        body: Box::new(node(Statement::Empty))
+    })]);
+    assert_eq!(body("for (x in o) ;"), vec![node(Statement::ForIn {
+        left: ForTarget::Pattern(binding("x")),
+        right: ident("o"),
+        body: Box::new(node(Statement::Empty))
+    })]);
+    assert_eq!(body("for (const [k, v] of m) ;"), vec![node(Statement::ForOf {
+        left: ForTarget::Variable(node(VariableDeclaration {
+            kind: VariableKind::Const,
+            declarations: vec![node(VariableDeclarator {
+                id: node(Pattern::Array(vec![Some(binding("k")), Some(binding("v"))])),
+                init: None
+            })]
+        })),
+        right: ident("m"),
+        body: Box::new(node(Statement::Empty)),
+        is_await: false
+    })]);
+    assert_eq!(body("for (let in o) ;").len(), 1);
+    match body("for (a.b of c) { break }")[0].item {
+        Statement::ForOf { left: ForTarget::Pattern(Node { item: Pattern::Expression(_), .. }), .. } => (),
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+
+    // `in` only ends the initializer at the top level of the head
+    let stmts = body("for (var a = (b in c), d = [e in f], g = h => i; j in k;) ;");
+    match stmts[0].item {
+        Statement::For { init: Some(ForInit::Variable(ref declaration)), ref test, .. } => {
+            assert_eq!(declaration.item.declarations.len(), 3);
+            assert!(test.is_some());
+        }
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+    assert_eq!(syntax_error("for (a = b in c) ;"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert!(parse("for (a ? b in c : d;;); for (var x = a ? b in c : d;;);").is_ok());
+    assert_eq!(syntax_error("for (a ? b : c in d;;);"), SyntaxErrorType::InvalidAssignmentTarget);
+    assert_eq!(syntax_error("for (var a, b in c) ;"), SyntaxErrorType::Unexpected(TokenType::In));
+    assert_eq!(syntax_error("for (const a; a;) ;"), SyntaxErrorType::MissingInitializer);
+    assert_eq!(syntax_error("for (var [a]; a;) ;"), SyntaxErrorType::MissingInitializer);
+    assert_eq!(syntax_error("for (a of b, c) ;"), SyntaxErrorType::Unexpected(TokenType::Comma));
+    let let_token = SyntaxErrorType::Unexpected(TokenType::Contextual(Contextual::Let));
+    assert_eq!(syntax_error("for (let.x of y) ;"), let_token);
+    assert!(parse("for (let of x) ;").is_err());
+    assert_eq!(syntax_error("for (async of x) ;"), SyntaxErrorType::Unexpected(TokenType::Contextual(Contextual::Async)));
+    assert!(parse("for (let.x in y) ; for (let.x; ;) ; for (async of => {}; ;) ; for (async in x) ;").is_ok());
+    assert!(parse("async function f() { for await (async of x) ; }").is_ok());
+
+    // `for await` needs a context where `await` is reserved
+    let program = JsContext::new().parse_module(String::from("for await (const x of xs) ;")).unwrap();
+    match program.body[0].item {
+        Statement::ForOf { is_await, .. } => assert!(is_await),
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+    assert!(JsContext::new().parse_module(String::from("for await (x in xs) ;")).is_err());
+    assert!(parse("for await (x of xs) ;").is_err());
+    assert!(parse("async function f() { for await (x of y) ; }").is_ok());
+    assert!(parse("(async () => { for await (const x of y) ; })").is_ok());
+    assert!(parse("async function f() { () => { for await (x of y) ; } }").is_err());
+}
+
+#[test]
+fn test_async_functions() {
+    match body("async function f(a) { await a }")[0].item {
+        Statement::Declaration(Declaration::Function(Function { is_async, ref body, .. })) => {
+            assert!(is_async);
+            assert_eq!(body.item, vec![expr_stmt(Expression::Await(Box::new(ident("a"))))]);
+        }
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+    match body("(async function () {})")[0].item {
+        Statement::Expression(Node { item: Expression::Function(Function { is_async, .. }), .. }) => assert!(is_async),
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+    assert_eq!(body("async x => await x"), vec![expr_stmt(Expression::Arrow {
+        params: vec![binding("x")],
+        body: ArrowBody::Expression(Box::new(node(Expression::Await(Box::new(ident("x")))))),
+        is_async: true
+    })]);
+    assert_eq!(body("async (a, ...b) => 1"), vec![expr_stmt(Expression::Arrow {
+        params: vec![binding("a"), node(Pattern::Rest(Box::new(binding("b"))))],
+        body: ArrowBody::Expression(Box::new(int(1))),
+        is_async: true
+    })]);
+
+    // without the arrow or on another line `async` is an identifier
+    assert_eq!(body("async(a)"), vec![expr_stmt(Expression::Call {
+        callee: Box::new(ident("async")),
+        arguments: vec![ident("a")]
+    })]);
+    assert_eq!(body("async\nfunction f() {}").len(), 2);
+    assert!(parse("new async()").is_ok());
+    assert_eq!(body("async\nx => x").len(), 2);
+    assert!(parse("function f() { await x }").is_err());
+    assert!(parse("async function f() { function g() { await x } }").is_err());
+    assert!(parse("if (a) async function f() {}").is_err());
+    assert!(parse("({ async m() { await x }, async *g() { yield await x }, async: 1, async })").is_ok());
+}
+
+#[test]
+fn test_do_while() {
+    let update = || expr_stmt(Expression::Update {
+        operator: UpdateOperator::Increment,
+        prefix: false,
+        argument: Box::new(ident("x"))
+    });
+    let test = node(Expression::Binary {
+        operator: BinaryOperator::SmallThan,
+        left: Box::new(ident("x")),
+        right: Box::new(int(10))
+    });
+    // the semicolon after `)` is inserted without a line break
+    assert_eq!(body("do x++; while (x < 10) foo()"), vec![
+        node(Statement::DoWhile { body: Box::new(update()), test: test.clone() }),
+        expr_stmt(Expression::Call { callee: Box::new(ident("foo")), arguments: vec![] })
+    ]);
+    assert_eq!(body("do { x++ } while (x < 10);"), vec![
+        node(Statement::DoWhile { body: Box::new(node(Statement::Block(vec![update()]))), test: test })
+    ]);
+    assert_eq!(body("do ; while (a) ;"), vec![node(Statement::DoWhile { body: Box::new(node(Statement::Empty)), test: ident("a") })]);
+    assert_eq!(syntax_error("do x++ while (x)"), SyntaxErrorType::Unexpected(TokenType::While));
+    assert_eq!(syntax_error("do ; until (x)"), SyntaxErrorType::Unexpected(TokenType::Identifier(Cow::from("until"))));
+}
+
+#[test]
+fn test_class() {
+    let method = |params: Vec<Node<Pattern>>, generator: bool, is_async: bool| node(Expression::Function(Function {
+        id: None,
+        params: params,
+        body: node(vec![]),
+        generator: generator,
+        is_async: is_async
+    }));
+    let key = |name: &str| node(PropertyKey::Identifier(String::from(name)));
+    assert_eq!(body("class A extends B { constructor(a) {} static get b() {} set b(v) {} *[c]() {} async d() {} static static() {} }"), vec![
+        node(Statement::Declaration(Declaration::Class(Class {
+            id: Some(name("A")),
+            super_class: Some(Box::new(ident("B"))),
+            body: node(vec![
+                node(ClassElement::Method { key: key("constructor"), value: method(vec![binding("a")], false, false), kind: MethodKind::Constructor, is_static: false }),
+                node(ClassElement::Method { key: key("b"), value: method(vec![], false, false), kind: MethodKind::Get, is_static: true }),
+                node(ClassElement::Method { key: key("b"), value: method(vec![binding("v")], false, false), kind: MethodKind::Set, is_static: false }),
+                node(ClassElement::Method { key: node(PropertyKey::Computed(ident("c"))), value: method(vec![], true, false), kind: MethodKind::Method, is_static: false }),
+                node(ClassElement::Method { key: key("d"), value: method(vec![], false, true), kind: MethodKind::Method, is_static: false }),
+                node(ClassElement::Method { key: key("static"), value: method(vec![], false, false), kind: MethodKind::Method, is_static: true })
+            ])
+        })))
+    ]);
+    assert_eq!(body("(class extends f() {});"), vec![expr_stmt(Expression::Class(Class {
+        id: None,
+        super_class: Some(Box::new(node(Expression::Call { callee: Box::new(ident("f")), arguments: vec![] }))),
+        body: node(vec![])
+    }))]);
+    assert_eq!(body("var C = class D { get() {}; set() {} static() {} };").len(), 1);
+    match body("class A { async *m() { await a } }")[0].item {
+        Statement::Declaration(Declaration::Class(ref class)) => match class.body.item[0].item {
+            ClassElement::Method { value: Node { item: Expression::Function(ref function), .. }, .. } => {
+                assert!(function.generator && function.is_async);
+                assert_eq!(function.body.item, vec![expr_stmt(Expression::Await(Box::new(ident("a"))))]);
+            }
+            ref element => panic!("unexpected {:?}", element)
+        },
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+
+    // class bodies are strict
+    assert_eq!(syntax_error("class A { m() { var let } }"), SyntaxErrorType::ReservedWord(String::from("let")));
+    assert_eq!(syntax_error("class yield {}"), SyntaxErrorType::ReservedWord(String::from("yield")));
+    assert_eq!(syntax_error("class A { constructor() {} 'constructor'() {} }"), SyntaxErrorType::InvalidConstructor);
+    assert_eq!(syntax_error("class A { get constructor() {} }"), SyntaxErrorType::InvalidConstructor);
+    assert!(parse("class A { static constructor() {} constructor() {} }").is_ok());
+    assert!(parse("class {}").is_err());
+    assert_eq!(syntax_error("class A { m() { with (a) {} } }"), SyntaxErrorType::StrictMode("`with` statement"));
+    assert_eq!(syntax_error("class A { m() { return 017 } }"), SyntaxErrorType::StrictMode("legacy octal literal"));
+    assert_eq!(syntax_error("class A { x = '\\017' }"), SyntaxErrorType::StrictMode("octal escape sequence"));
+    assert_eq!(syntax_error("class A { m(a, a) {} }"), SyntaxErrorType::DuplicateParameter(String::from("a")));
+    assert_eq!(syntax_error("class A { m() { function f(a, a) {} } }"), SyntaxErrorType::DuplicateParameter(String::from("a")));
+    assert_eq!(syntax_error("class A { static prototype() {} }"), SyntaxErrorType::StaticPrototype);
+    assert_eq!(syntax_error("class A { static 'prototype' = 1 }"), SyntaxErrorType::StaticPrototype);
+    assert!(parse("class A { prototype() {} static [prototype]() {} } with (a) {} function f(a, a) {}").is_ok());
+
+    // a getter has no parameters, a setter exactly one
+    assert!(parse("class A { get a() {} set a([b, c] = d) {} static set [e](f) {} }").is_ok());
+    for source in &["class A { get a(b) {} }", "class A { set a() {} }", "class A { set a(b, c) {} }",
+                    "class A { set a(...b) {} }", "class A { static get #a(b) {} }"] {
+        assert_eq!(syntax_error(source), SyntaxErrorType::InvalidAccessor, "{}", source);
+    }
+
+    // `super()` only in the constructor of a derived class, `super.x` only in methods
+    assert!(parse("class A extends B { constructor() { super(); () => super.x } m() { super.m() } x = super.x; static { super.y } }").is_ok());
+    assert!(parse("class A extends B { constructor() { var f = () => super(); ({ m() { super.m() } }) } }").is_ok());
+    assert!(parse("function f() { new.target; () => new.target } class A { x = new.target }").is_ok());
+    for source in &["class A { constructor() { super() } }", "class A extends B { m() { super() } }",
+                    "class A extends B { constructor() { function f() { super() } } }",
+                    "class A extends B { x = super() }", "function f() { super() }", "super.x",
+                    "function f() { super.x }", "class A { m() { function f() { super.x } } }", "() => super.x"] {
+        assert_eq!(syntax_error(source), SyntaxErrorType::InvalidSuper, "{}", source);
+    }
+    assert_eq!(syntax_error("new.target"), SyntaxErrorType::InvalidNewTarget);
+    assert_eq!(syntax_error("() => new.target"), SyntaxErrorType::InvalidNewTarget);
+}
+
+#[test]
+fn test_class_fields() {
+    let private = |name: &str| node(PropertyKey::Private(String::from(name)));
+    let member = |name: &str| node(Expression::Member {
+        object: Box::new(node(Expression::This)),
+        property: Box::new(node(Expression::PrivateName(String::from(name)))),
+        computed: false
+    });
+    assert_eq!(body("class A { #count = 0; static x\n y; static { this.#count } has(o) { return #count in o } }"), vec![
+        node(Statement::Declaration(Declaration::Class(Class {
+            id: Some(name("A")),
+            super_class: None,
+            body: node(vec![
+                node(ClassElement::Field { key: private("count"), value: Some(int(0)), is_static: false }),
+                node(ClassElement::Field { key: node(PropertyKey::Identifier(String::from("x"))), value: None, is_static: true }),
+                node(ClassElement::Field { key: node(PropertyKey::Identifier(String::from("y"))), value: None, is_static: false }),
+                node(ClassElement::StaticBlock(vec![expr_stmt(member("count").item)])),
+                node(ClassElement::Method {
+                    key: node(PropertyKey::Identifier(String::from("has"))),
+                    value: node(Expression::Function(Function {
+                        id: None,
+                        params: vec![binding("o")],
+                        body: node(vec![node(Statement::Return(Some(node(Expression::Binary {
+                            operator: BinaryOperator::In,
+                            left: Box::new(node(Expression::PrivateName(String::from("count")))),
+                            right: Box::new(ident("o"))
+                        }))))]),
+                        generator: false,
+                        is_async: false
+                    })),
+                    kind: MethodKind::Method,
+                    is_static: false
+                })
+            ])
+        })))
+    ]);
+    // uses may come before the declaration and from nested classes
+    assert!(parse("class A { m() { return class { n(o) { return o.#p } } } get #p() {} set #p(v) {} static #q() {} }").is_ok());
+    assert!(parse("class A { static = 1; get; set = 2; async }").is_ok());
+    match body("class A { async\nm() {} }")[0].item {
+        Statement::Declaration(Declaration::Class(ref class)) => match class.body.item[..] {
+            [Node { item: ClassElement::Field { .. }, .. }, Node { item: ClassElement::Method { .. }, .. }] => (),
+            ref elements => panic!("unexpected {:?}", elements)
+        },
+        ref stmt => panic!("unexpected {:?}", stmt)
+    }
+
+    assert_eq!(syntax_error("class A { m() { this.#x } }"), SyntaxErrorType::UndeclaredPrivateName(String::from("x")));
+    assert_eq!(syntax_error("this.#x"), SyntaxErrorType::UndeclaredPrivateName(String::from("x")));
+    assert_eq!(syntax_error("class A { #x; #x() {} }"), SyntaxErrorType::DuplicatePrivateName(String::from("x")));
+    assert_eq!(syntax_error("class A { get #x() {} static set #x(v) {} }"), SyntaxErrorType::DuplicatePrivateName(String::from("x")));
+    assert_eq!(syntax_error("class A { #constructor }"), SyntaxErrorType::InvalidConstructor);
+    assert_eq!(syntax_error("class A { constructor = 1 }"), SyntaxErrorType::InvalidConstructor);
+    assert_eq!(syntax_error("class A { #x; m(o) { return 1 + #x in o } }"), SyntaxErrorType::Unexpected(TokenType::PrivateName(Cow::from("x"))));
+    assert_eq!(syntax_error("class A { #x; m() { #x } }"), SyntaxErrorType::Unexpected(TokenType::PrivateName(Cow::from("x"))));
+    assert_eq!(syntax_error("({ #x: 1 })"), SyntaxErrorType::Unexpected(TokenType::PrivateName(Cow::from("x"))));
+
+    assert_eq!(syntax_error("class A { static { await } }"), SyntaxErrorType::ReservedWord(String::from("await")));
+    assert_eq!(syntax_error("class A { static { () => await } }"), SyntaxErrorType::ReservedWord(String::from("await")));
+    assert_eq!(syntax_error("class A { static { return } }"), SyntaxErrorType::Unexpected(TokenType::Return));
+    assert_eq!(syntax_error("return"), SyntaxErrorType::Unexpected(TokenType::Return));
+    assert_eq!(syntax_error("class A { static { arguments } }"), SyntaxErrorType::InvalidArguments);
+    assert_eq!(syntax_error("class A { x = arguments }"), SyntaxErrorType::InvalidArguments);
+    assert_eq!(syntax_error("class A { x = () => arguments }"), SyntaxErrorType::InvalidArguments);
+    assert_eq!(syntax_error("class A { #x; m() { delete this.#x } }"), SyntaxErrorType::DeletePrivateName);
+    assert_eq!(syntax_error("class A { #x; m() { delete (this.#x) } }"), SyntaxErrorType::DeletePrivateName);
+    assert!(parse("class A { static { function f() { await; return arguments } () => { return } } x = function () { return arguments } }").is_ok());
+    assert!(parse("class A { #x; m() { delete this.x; return arguments } }").is_ok());
+}
+
+#[test]
+fn test_lexical_declaration() {
+    assert_eq!(body("const x = 1, y = 2;"), vec![node(Statement::Declaration(Declaration::Variable(VariableDeclaration {
+        kind: VariableKind::Const,
+        declarations: vec![
+            node(VariableDeclarator { id: binding("x"), init: Some(int(1)) }),
+            node(VariableDeclarator { id: binding("y"), init: Some(int(2)) })
+        ]
+    })))]);
+    assert_eq!(body("let a, { b, c: [d] } = e\nf"), vec![
+        node(Statement::Declaration(Declaration::Variable(VariableDeclaration {
+            kind: VariableKind::Let,
+            declarations: vec![
+                node(VariableDeclarator { id: binding("a"), init: None }),
+                node(VariableDeclarator {
+                    id: node(Pattern::Object(vec![
+                        node(PatternProperty::Property { key: node(PropertyKey::Identifier(String::from("b"))), value: binding("b"), shorthand: true }),
+                        node(PatternProperty::Property {
+                            key: node(PropertyKey::Identifier(String::from("c"))),
+                            value: node(Pattern::Array(vec![Some(binding("d"))])),
+                            shorthand: false
+                        })
+                    ])),
+                    init: Some(ident("e"))
+                })
+            ]
+        }))),
+        expr_stmt(Expression::Identifier(String::from("f")))
+    ]);
+    assert_eq!(body("{ let\nx }").len(), 1);
+
+    // `let` is still an identifier in sloppy scripts where no declaration can start
+    assert_eq!(body("let = 5"), vec![expr_stmt(Expression::Assignment {
+        operator: AssignmentOperator::Equal,
+        left: Box::new(binding("let")),
+        right: Box::new(int(5))
+    })]);
+    assert_eq!(body("a = let[0]"), vec![expr_stmt(Expression::Assignment {
+        operator: AssignmentOperator::Equal,
+        left: Box::new(binding("a")),
+        right: Box::new(node(Expression::Member {
+            object: Box::new(ident("let")),
+            property: Box::new(int(0)),
+            computed: true
+        }))
+    })]);
+    assert!(parse("let.a; let(); let\n++a").is_ok());
+
+    assert_eq!(syntax_error("const x;"), SyntaxErrorType::MissingInitializer);
+    assert_eq!(syntax_error("const x = 1, y;"), SyntaxErrorType::MissingInitializer);
+    assert_eq!(syntax_error("let [a];"), SyntaxErrorType::MissingInitializer);
+    assert_eq!(syntax_error("let [let] = a"), SyntaxErrorType::ReservedWord(String::from("let")));
+    assert_eq!(syntax_error("'use strict'; let = 5"), SyntaxErrorType::ReservedWord(String::from("let")));
+    assert_eq!(syntax_error("if (a) const b = 1;"), SyntaxErrorType::Unexpected(TokenType::Const));
+
+    // only a statement may follow `if (a)`, so `let` ends at the line break
+    assert_eq!(body("if (a) let\nx = 1"), vec![
+        node(Statement::If {
+            test: ident("a"),
+            consequent: Box::new(expr_stmt(Expression::Identifier(String::from("let")))),
+            alternate: None
+        }),
+        expr_stmt(Expression::Assignment {
+            operator: AssignmentOperator::Equal,
+            left: Box::new(binding("x")),
+            right: Box::new(int(1))
+        })
+    ]);
+    assert!(parse("while (a) let\nx").is_ok());
+    assert_eq!(syntax_error("if (a) let x = 1"), SyntaxErrorType::Unexpected(TokenType::Contextual(Contextual::Let)));
+    assert!(parse("if (a) let\n[x] = b").is_err());
+    assert_eq!(body("let\nx = 1").len(), 1);
+}
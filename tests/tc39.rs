@@ -13158,7 +13158,7 @@ fn parse(file: &str) {
     };
     let context = &mut JsContext::new();
     let chars = context.parse(js);
-    match chars {
+    match chars.map_err(|err| err.error_type) {
         Err(ErrorType::SyntaxError(t)) => {
             if syntax {
                 panic!("assert {:?}", t)